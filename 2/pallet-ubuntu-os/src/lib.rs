@@ -8,6 +8,18 @@
 //! - Validator voting mechanism
 //! - Operation categorization and permissions
 //! - Event logging for audit trails
+//! - Turnout-biased weighted consensus: validator voting weight plus a turnout gate and a
+//!   negative turnout bias on the approval threshold
+//! - VRF-seeded delay tranches bound how many validators must vote per operation, with
+//!   no-show escalation to the next tranche when the current one goes quiet
+//! - Timelocked execution: approved high-consensus operations wait out `ExecutionDelay`
+//!   behind a preimage lookup before they execute, giving device owners a veto window
+//! - Conviction-locked votes: validators may amplify their vote's weight by locking up for
+//!   longer, via `VoteLocks`
+//! - Collective-style proposal lifecycle: `close_operation` lets any validator force a decision
+//!   once the outcome is mathematically settled, or once `VotingPeriod` elapses (falling back to
+//!   a governance-set `PrimeValidator`'s vote for absentees); `veto_operation` lets a governed
+//!   device owner immediately deny and archive an operation originating from their device
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -17,10 +29,12 @@ use frame_support::{
     weights::{Weight, DispatchClass},
     dispatch::{DispatchResult, DispatchError},
     codec::{Encode, Decode},
+    BoundedVec,
 };
 use sp_std::{vec::Vec, collections::btree_map::BTreeMap};
 use frame_system::ensure_signed;
-use sp_runtime::traits::{Zero, Saturating};
+use sp_runtime::{traits::{Zero, Saturating, Hash}, Permill};
+use sp_io::hashing::blake2_256;
 
 /// OS operation types that require consensus
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
@@ -49,13 +63,15 @@ pub enum Vote {
 
 /// OS operation request
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
-pub struct OSOperation<AccountId> {
+pub struct OSOperation<AccountId, Hash> {
     /// Who is requesting the operation
     pub requester: AccountId,
     /// Type of operation
     pub operation_type: OperationType,
-    /// Operation details (command, file path, etc.)
-    pub details: Vec<u8>,
+    /// Blake2 hash of the operation details (command, file path, etc.). The actual payload is
+    /// kept out of this struct and registered separately via `note_details_preimage`, so large
+    /// operations don't bloat `PendingOperations`/`OperationHistory`.
+    pub details_hash: Hash,
     /// Hostname/device that originated the request
     pub origin_device: Vec<u8>,
     /// Block number when requested
@@ -73,6 +89,10 @@ pub struct ConsensusResult {
     pub deny_votes: u32,
     /// Whether consensus was reached
     pub approved: bool,
+    /// Share of eligible voting weight that turned out (cast any vote, including Abstain)
+    pub turnout: Permill,
+    /// Approval fraction required to pass, after the negative turnout bias was applied
+    pub effective_threshold: Permill,
 }
 
 /// Configure the pallet by specifying the parameters and types on which it depends.
@@ -86,8 +106,46 @@ pub trait Trait: frame_system::Trait {
     /// Minimum number of votes required for consensus
     type MinimumVotes: Get<u32>;
 
-    /// Percentage of approve votes needed (out of 100)
+    /// Percentage of approve votes needed (out of 100), before the turnout bias is applied
     type ApprovalThreshold: Get<u32>;
+
+    /// Minimum share of eligible voting weight that must turn out before consensus is
+    /// evaluated at all, regardless of how lopsided the votes cast so far are
+    type TurnoutThreshold: Get<Permill>;
+
+    /// Source of on-chain randomness used to derive each operation's tranche assignment seed
+    type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+
+    /// Number of delay tranches an operation's validators are spread across
+    type MaxTranches: Get<u32>;
+
+    /// How many blocks a tranche is given to vote before the next tranche opens early to
+    /// recruit replacements for validators who haven't shown up
+    type NoShowDelay: Get<Self::BlockNumber>;
+
+    /// Delay, in blocks, between an operation clearing consensus and it actually executing.
+    /// Gives device owners a mandatory veto window for high-consensus operations.
+    type ExecutionDelay: Get<Self::BlockNumber>;
+
+    /// Base lock period applied at conviction level 1; doubles for each level beyond that
+    type VoteLockPeriod: Get<Self::BlockNumber>;
+
+    /// How long an operation stays open for voting before `close_operation` may force a
+    /// decision using the prime validator's vote as the default for absentees
+    type VotingPeriod: Get<Self::BlockNumber>;
+
+    /// Maximum byte length of a `note_details_preimage` payload
+    type MaxDetailsLength: Get<u32>;
+
+    /// Maximum number of operations that may share a single block's `NoShowCheckAt` or
+    /// `ScheduledExecutions` entry. `submit_operation` is open to any signed account, so
+    /// without a bound repeated calls could pile an unbounded number of operations onto one
+    /// block.
+    type MaxOperationsPerBlock: Get<u32>;
+
+    /// Maximum number of approved validators, also bounding how many no-show entries a single
+    /// operation's `NoShows` can accumulate across its tranches
+    type MaxValidators: Get<u32>;
 }
 
 // Storage items for the pallet
@@ -95,7 +153,7 @@ decl_storage! {
     trait Store for Module<T: Trait> as UbuntuOS {
         /// Pending OS operations awaiting consensus
         PendingOperations get(fn pending_operations):
-            map hasher(blake2_128_concat) u32 => Option<OSOperation<T::AccountId>>;
+            map hasher(blake2_128_concat) u32 => Option<OSOperation<T::AccountId, T::Hash>>;
 
         /// Votes for each operation
         OperationVotes get(fn operation_votes):
@@ -109,19 +167,111 @@ decl_storage! {
         ApprovedValidators get(fn approved_validators):
             map hasher(blake2_128_concat) T::AccountId => bool;
 
+        /// Number of approved validators, kept in sync with `ApprovedValidators` so
+        /// `on_initialize` can weight its per-validator no-show scan without an unbounded
+        /// `iter()` just to count them.
+        ApprovedValidatorCount get(fn approved_validator_count): u32 = 0;
+
+        /// Per-validator voting weight, settable by governance. Defaults to 1 so an
+        /// unweighted validator set behaves like the original one-validator-one-vote scheme.
+        VotingWeight get(fn voting_weight):
+            map hasher(blake2_128_concat) T::AccountId => u32 = 1;
+
+        /// Per-operation randomness seed, derived from `T::Randomness` at submission time and
+        /// used to assign validators to delay tranches
+        OperationSeed get(fn operation_seed):
+            map hasher(blake2_128_concat) u32 => T::Hash;
+
+        /// Each validator's assigned delay tranche for an operation, computed lazily the first
+        /// time it's needed and cached here so it's verifiable on-chain afterwards
+        AssignedTranche get(fn assigned_tranche):
+            double_map hasher(blake2_128_concat) u32, hasher(blake2_128_concat) T::AccountId
+            => Option<u32>;
+
+        /// The tranche currently open for voting on an operation
+        CurrentTranche get(fn current_tranche):
+            map hasher(blake2_128_concat) u32 => u32;
+
+        /// Block number at which the current tranche opened, used to detect no-shows
+        TrancheOpenedAt get(fn tranche_opened_at):
+            map hasher(blake2_128_concat) u32 => T::BlockNumber;
+
+        /// Operations due a no-show check at a given block (`tranche_opened_at + NoShowDelay`),
+        /// so `on_initialize` only visits operations whose window has actually elapsed instead
+        /// of scanning every entry of `PendingOperations` on every block.
+        NoShowCheckAt get(fn no_show_check_at):
+            map hasher(blake2_128_concat) T::BlockNumber => BoundedVec<u32, T::MaxOperationsPerBlock>;
+
+        /// Validators recorded as no-shows for an operation across all of its tranches; kept as
+        /// part of the operation's audit trail alongside `OperationHistory`
+        NoShows get(fn no_shows):
+            map hasher(blake2_128_concat) u32 => BoundedVec<T::AccountId, T::MaxValidators>;
+
+        /// Raw bytes noted for an operation's `details_hash`, keyed by that hash
+        Preimages get(fn preimages):
+            map hasher(blake2_128_concat) T::Hash => Option<Vec<u8>>;
+
+        /// Number of operations currently referencing a noted preimage; the preimage is
+        /// dropped once this reaches zero
+        PreimageRequestCount get(fn preimage_request_count):
+            map hasher(blake2_128_concat) T::Hash => u32;
+
+        /// Approved high-consensus operations queued for execution, keyed by the block number
+        /// at which their veto window (`T::ExecutionDelay`) expires
+        ScheduledExecutions get(fn scheduled_executions):
+            map hasher(blake2_128_concat) T::BlockNumber => BoundedVec<u32, T::MaxOperationsPerBlock>;
+
+        /// Block an approved operation is scheduled to execute at, present from the moment
+        /// `run_consensus` schedules it until `on_initialize` drains it; lets `cancel_operation`
+        /// and `veto_operation` tell a genuinely-scheduled operation_id from one that was never
+        /// submitted, already executed, or never needed scheduling in the first place
+        PendingExecution get(fn pending_execution):
+            map hasher(blake2_128_concat) u32 => Option<T::BlockNumber>;
+
+        /// Approved operations that were vetoed before their scheduled execution ran
+        Vetoed get(fn vetoed):
+            map hasher(blake2_128_concat) u32 => bool;
+
+        /// Conviction level (0-6) a validator attached to their vote on an operation
+        VoteConvictions get(fn vote_convictions):
+            double_map hasher(blake2_128_concat) u32, hasher(blake2_128_concat) T::AccountId
+            => u8;
+
+        /// Block at which a validator's conviction-vote lock expires. A value at or before the
+        /// current block means the validator is unlocked.
+        VoteLocks get(fn vote_locks):
+            map hasher(blake2_128_concat) T::AccountId => T::BlockNumber;
+
+        /// Block at which an operation's voting period elapses and `close_operation` may force
+        /// a decision
+        VotingDeadline get(fn voting_deadline):
+            map hasher(blake2_128_concat) u32 => T::BlockNumber;
+
+        /// Governance-set validator whose vote is used as the default for every validator who
+        /// abstained or never voted when a period-based `close_operation` forces a decision
+        PrimeValidator get(fn prime_validator): Option<T::AccountId>;
+
+        /// Account bound as a device's owner by `register_device`, used to authorize `veto_operation`
+        DeviceOwners get(fn device_owners):
+            map hasher(blake2_128_concat) Vec<u8> => Option<T::AccountId>;
+
         /// Device trust levels (0-100)
         DeviceTrust get(fn device_trust):
             map hasher(blake2_128_concat) Vec<u8> => u32;
 
         /// Operation history for audit trails
         OperationHistory get(fn operation_history):
-            map hasher(blake2_128_concat) u32 => Option<(OSOperation<T::AccountId>, ConsensusResult)>;
+            map hasher(blake2_128_concat) u32 => Option<(OSOperation<T::AccountId, T::Hash>, ConsensusResult)>;
     }
 }
 
 // Events emitted by the pallet
 decl_event!(
-    pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
+    pub enum Event<T>
+    where
+        AccountId = <T as frame_system::Trait>::AccountId,
+        Hash = <T as frame_system::Trait>::Hash,
+    {
         /// An OS operation was submitted for consensus [operation_id, requester]
         OperationSubmitted(u32, AccountId),
 
@@ -131,14 +281,35 @@ decl_event!(
         /// Consensus was reached for an operation [operation_id, approved]
         ConsensusReached(u32, bool),
 
+        /// An approved high-consensus operation was scheduled for execution once its veto
+        /// window passes [operation_id]
+        OperationScheduled(u32),
+
         /// An operation was executed [operation_id]
         OperationExecuted(u32),
 
+        /// A scheduled operation was vetoed before it could execute [operation_id]
+        OperationCancelled(u32),
+
+        /// A preimage was noted for an operation's details hash [hash]
+        PreimageNoted(Hash),
+
         /// A validator was added [validator]
         ValidatorAdded(AccountId),
 
         /// Device trust level was updated [device, trust_level]
         DeviceTrustUpdated(Vec<u8>, u32),
+
+        /// A validator failed to vote within their tranche window, so the next tranche opened
+        /// early to recruit replacements [operation_id, validator]
+        ValidatorNoShow(u32, AccountId),
+
+        /// A validator force-closed voting on an operation [operation_id, closer]
+        OperationClosed(u32, AccountId),
+
+        /// The operation's origin device owner vetoed it, either before validators decided or
+        /// while it awaited execution after approval [operation_id, device_owner]
+        OperationVetoed(u32, AccountId),
     }
 );
 
@@ -157,6 +328,34 @@ decl_error! {
         InvalidOperation,
         /// Insufficient permissions
         InsufficientPermissions,
+        /// This validator's delay tranche hasn't opened yet
+        NotYetAssigned,
+        /// Conviction level must be between 0 and 6
+        InvalidConviction,
+        /// This validator's conviction-vote lock hasn't expired yet
+        StillLocked,
+        /// Voting weight can't be reduced while a conviction-vote lock is active
+        VoteLocked,
+        /// Neither the voting period has elapsed nor is the outcome mathematically decided yet
+        VotingStillOpen,
+        /// Caller does not own the operation's origin device
+        NotDeviceOwner,
+        /// This device has already been registered by another owner
+        DeviceAlreadyOwned,
+        /// `details_hash` has no preimage noted for it yet
+        PreimageNotFound,
+        /// `note_details_preimage`'s payload exceeds `MaxDetailsLength`
+        DetailsTooLong,
+        /// `unnote_details_preimage` only removes preimages no operation references anymore
+        PreimageStillReferenced,
+        /// `operation_id` isn't a scheduled, unexecuted operation awaiting its veto window
+        OperationNotScheduled,
+        /// This operation has already been vetoed
+        AlreadyVetoed,
+        /// This block already has `MaxOperationsPerBlock` operations scheduled against it
+        TooManyOperationsThisBlock,
+        /// Already at `MaxValidators` approved validators
+        TooManyValidators,
     }
 }
 
@@ -172,23 +371,116 @@ decl_module! {
         // Constants
         const MinimumVotes: u32 = T::MinimumVotes::get();
         const ApprovalThreshold: u32 = T::ApprovalThreshold::get();
+        const TurnoutThreshold: Permill = T::TurnoutThreshold::get();
+        const MaxTranches: u32 = T::MaxTranches::get();
+        const MaxDetailsLength: u32 = T::MaxDetailsLength::get();
+        const MaxOperationsPerBlock: u32 = T::MaxOperationsPerBlock::get();
+        const MaxValidators: u32 = T::MaxValidators::get();
+        const VoteLockPeriod: T::BlockNumber = T::VoteLockPeriod::get();
+
+        /// Advance the tranche clock for operations whose no-show window elapses this block,
+        /// opening the next tranche early wherever the currently assigned validators have gone
+        /// quiet for too long.
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let mut weight: Weight = 0;
+
+            // Indexed by the block each operation's no-show window is actually due, same
+            // pattern as the sibling pallet's `PendingDeadlines`, so this only touches
+            // operations due this block rather than scanning the whole `PendingOperations` map
+            // (and, within it, every approved validator) on every block regardless of backlog.
+            let per_operation_weight: Weight = 10_000
+                .saturating_add((Self::approved_validator_count() as Weight).saturating_mul(1_000));
+
+            for operation_id in NoShowCheckAt::<T>::take(now) {
+                weight = weight.saturating_add(10_000);
+
+                if PendingOperations::<T>::get(operation_id).is_none() {
+                    // Already reached a terminal state before its no-show check came due.
+                    continue;
+                }
+                weight = weight.saturating_add(per_operation_weight);
+
+                let current_tranche = Self::current_tranche(operation_id);
+                let mut no_shows = Vec::new();
+                for (validator, _) in ApprovedValidators::<T>::iter() {
+                    let tranche = Self::assigned_tranche_of(operation_id, &validator);
+                    if tranche == current_tranche
+                        && Self::operation_votes(operation_id, &validator).is_none()
+                    {
+                        no_shows.push(validator);
+                    }
+                }
+
+                if no_shows.is_empty() {
+                    continue;
+                }
+
+                for validator in no_shows {
+                    // Bounded by MaxValidators, same as ApprovedValidators itself, so this
+                    // should never actually overflow; on_initialize has no way to report an
+                    // error, so a would-be overflow is silently dropped from the audit trail
+                    // rather than panicking or stalling the no-show scan.
+                    let _ = NoShows::<T>::try_mutate(&operation_id, |list| list.try_push(validator.clone()));
+                    Self::deposit_event(RawEvent::ValidatorNoShow(operation_id, validator));
+                }
+
+                CurrentTranche::insert(&operation_id, current_tranche.saturating_add(1));
+                TrancheOpenedAt::<T>::insert(&operation_id, now);
+                let _ = NoShowCheckAt::<T>::try_mutate(
+                    now.saturating_add(T::NoShowDelay::get()),
+                    |list| list.try_push(operation_id),
+                );
+            }
+
+            // Drain operations whose execution-delay veto window has just expired
+            for operation_id in ScheduledExecutions::<T>::take(now) {
+                weight = weight.saturating_add(10_000);
+                PendingExecution::<T>::remove(&operation_id);
+
+                if Self::vetoed(operation_id) {
+                    Vetoed::remove(&operation_id);
+                    if let Some((operation, _result)) = Self::operation_history(operation_id) {
+                        Self::release_preimage(&operation.details_hash);
+                    }
+                    continue;
+                }
+
+                if let Some((operation, _result)) = Self::operation_history(operation_id) {
+                    if Self::preimages(&operation.details_hash).is_some() {
+                        Self::deposit_event(RawEvent::OperationExecuted(operation_id));
+                    }
+
+                    // Unrequest the preimage now that this operation is done with it
+                    Self::release_preimage(&operation.details_hash);
+                }
+            }
 
-        /// Submit an OS operation for consensus
+            weight
+        }
+
+        /// Submit an OS operation for consensus. `details_hash` must already have been
+        /// registered via `note_details_preimage`; this call takes out this operation's
+        /// reference on it, released once the operation reaches a terminal state.
         #[weight = 10_000]
         pub fn submit_operation(
             origin,
             operation_type: OperationType,
-            details: Vec<u8>,
+            details_hash: T::Hash,
             origin_device: Vec<u8>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
+            if !Preimages::<T>::contains_key(&details_hash) {
+                return Err(Error::<T>::PreimageNotFound.into());
+            }
+            PreimageRequestCount::<T>::mutate(&details_hash, |count| *count = count.saturating_add(1));
+
             let operation_id = Self::next_operation_id();
 
             let operation = OSOperation {
                 requester: who.clone(),
                 operation_type,
-                details,
+                details_hash,
                 origin_device,
                 requested_at: frame_system::Module::<T>::block_number().saturated_into::<u32>(),
             };
@@ -196,17 +488,31 @@ decl_module! {
             PendingOperations::<T>::insert(&operation_id, &operation);
             NextOperationId::mutate(|id| *id = id.saturating_add(1));
 
+            let (seed, _) = T::Randomness::random(&operation_id.encode());
+            let now = frame_system::Module::<T>::block_number();
+            OperationSeed::<T>::insert(&operation_id, seed);
+            CurrentTranche::insert(&operation_id, 0u32);
+            TrancheOpenedAt::<T>::insert(&operation_id, now);
+            NoShowCheckAt::<T>::try_mutate(
+                now.saturating_add(T::NoShowDelay::get()),
+                |list| list.try_push(operation_id),
+            ).map_err(|_| Error::<T>::TooManyOperationsThisBlock)?;
+            VotingDeadline::<T>::insert(&operation_id, now.saturating_add(T::VotingPeriod::get()));
+
             Self::deposit_event(RawEvent::OperationSubmitted(operation_id, who));
 
             Ok(())
         }
 
-        /// Vote on a pending operation (validators only)
+        /// Vote on a pending operation (validators only). `conviction` (0-6) amplifies the
+        /// weight this vote contributes to `check_consensus`, at the cost of locking the
+        /// validator out of reduced voting weight until the lock's expiry block is reached.
         #[weight = 10_000]
         pub fn vote_on_operation(
             origin,
             operation_id: u32,
             vote: Vote,
+            conviction: u8,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -215,10 +521,20 @@ decl_module! {
                 return Err(Error::<T>::NotValidator.into());
             }
 
+            if conviction > 6 {
+                return Err(Error::<T>::InvalidConviction.into());
+            }
+
             // Check if operation exists
             let operation = Self::pending_operations(operation_id)
                 .ok_or(Error::<T>::OperationNotFound)?;
 
+            // Check the caller's delay tranche has opened
+            let tranche = Self::assigned_tranche_of(operation_id, &who);
+            if tranche > Self::current_tranche(operation_id) {
+                return Err(Error::<T>::NotYetAssigned.into());
+            }
+
             // Check if already voted
             if Self::operation_votes(operation_id, &who).is_some() {
                 return Err(Error::<T>::AlreadyVoted.into());
@@ -226,6 +542,17 @@ decl_module! {
 
             // Record vote
             OperationVotes::<T>::insert(&operation_id, &who, &vote);
+            VoteConvictions::<T>::insert(&operation_id, &who, conviction);
+
+            if conviction > 0 {
+                let now = frame_system::Module::<T>::block_number();
+                let unlock_at = now.saturating_add(Self::lock_period_for(conviction));
+                VoteLocks::<T>::mutate(&who, |existing| {
+                    if unlock_at > *existing {
+                        *existing = unlock_at;
+                    }
+                });
+            }
 
             Self::deposit_event(RawEvent::VoteCast(operation_id, who, vote));
 
@@ -244,6 +571,12 @@ decl_module! {
             // In production, this would require governance/sudo
             ensure_signed(origin)?;
 
+            if !Self::approved_validators(&validator) {
+                if Self::approved_validator_count() >= T::MaxValidators::get() {
+                    return Err(Error::<T>::TooManyValidators.into());
+                }
+                ApprovedValidatorCount::mutate(|count| *count = count.saturating_add(1));
+            }
             ApprovedValidators::<T>::insert(&validator, true);
 
             Self::deposit_event(RawEvent::ValidatorAdded(validator));
@@ -269,52 +602,327 @@ decl_module! {
 
             Ok(())
         }
+
+        /// Set a validator's voting weight (governance only). A locked validator's weight
+        /// can't be reduced until their conviction-vote lock expires.
+        #[weight = 10_000]
+        pub fn set_voting_weight(
+            origin,
+            validator: T::AccountId,
+            weight: u32,
+        ) -> DispatchResult {
+            T::VotingOrigin::ensure_origin(origin)?;
+
+            if weight < Self::voting_weight(&validator) {
+                let now = frame_system::Module::<T>::block_number();
+                if now < Self::vote_locks(&validator) {
+                    return Err(Error::<T>::VoteLocked.into());
+                }
+            }
+
+            VotingWeight::<T>::insert(&validator, weight);
+
+            Ok(())
+        }
+
+        /// Clear the caller's conviction-vote lock once it has expired
+        #[weight = 10_000]
+        pub fn unlock(origin) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let now = frame_system::Module::<T>::block_number();
+            if now < Self::vote_locks(&who) {
+                return Err(Error::<T>::StillLocked.into());
+            }
+
+            VoteLocks::<T>::remove(&who);
+
+            Ok(())
+        }
+
+        /// Register the raw bytes behind an operation's `details_hash`, ahead of (or alongside)
+        /// `submit_operation` referencing it. Idempotent: re-noting the same payload just
+        /// overwrites the stored bytes without touching `PreimageRequestCount`, which instead
+        /// tracks how many *operations* currently reference the hash via `submit_operation`.
+        #[weight = 10_000]
+        pub fn note_details_preimage(origin, details: Vec<u8>) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            if details.len() as u32 > T::MaxDetailsLength::get() {
+                return Err(Error::<T>::DetailsTooLong.into());
+            }
+
+            let hash = T::Hashing::hash(&details);
+            Preimages::<T>::insert(&hash, &details);
+
+            Self::deposit_event(RawEvent::PreimageNoted(hash));
+
+            Ok(())
+        }
+
+        /// Remove a noted preimage that no operation currently references, so a noted-but-never
+        /// -submitted (or already-terminal) payload doesn't sit in storage forever at refcount 0
+        #[weight = 10_000]
+        pub fn unnote_details_preimage(origin, details_hash: T::Hash) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            if !Preimages::<T>::contains_key(&details_hash) {
+                return Err(Error::<T>::PreimageNotFound.into());
+            }
+
+            if Self::preimage_request_count(&details_hash) != 0 {
+                return Err(Error::<T>::PreimageStillReferenced.into());
+            }
+
+            Preimages::<T>::remove(&details_hash);
+
+            Ok(())
+        }
+
+        /// Veto a high-consensus operation that has cleared voting but hasn't executed yet
+        /// (governance only)
+        #[weight = 10_000]
+        pub fn cancel_operation(origin, operation_id: u32) -> DispatchResult {
+            T::VotingOrigin::ensure_origin(origin)?;
+
+            Self::cancel_scheduled_operation(operation_id)?;
+
+            Self::deposit_event(RawEvent::OperationCancelled(operation_id));
+
+            Ok(())
+        }
+
+        /// Set the prime validator, whose vote stands in for absentees when a period-based
+        /// `close_operation` forces a decision (governance only)
+        #[weight = 10_000]
+        pub fn set_prime_validator(origin, validator: T::AccountId) -> DispatchResult {
+            T::VotingOrigin::ensure_origin(origin)?;
+
+            PrimeValidator::<T>::put(validator);
+
+            Ok(())
+        }
+
+        /// Bind a device name to its owning account (governance only), authorizing that
+        /// account to later call `veto_operation` on operations originating from it. Gated
+        /// behind `VotingOrigin` rather than open self-registration, since an unauthenticated
+        /// claim would let whoever squats a device name first veto-deny any operation claiming
+        /// that `origin_device`, bypassing the validator quorum entirely.
+        #[weight = 10_000]
+        pub fn register_device(origin, device: Vec<u8>, owner: T::AccountId) -> DispatchResult {
+            T::VotingOrigin::ensure_origin(origin)?;
+
+            if DeviceOwners::<T>::contains_key(&device) {
+                return Err(Error::<T>::DeviceAlreadyOwned.into());
+            }
+
+            DeviceOwners::<T>::insert(&device, &owner);
+
+            Ok(())
+        }
+
+        /// Force a decision on an operation, callable by any approved validator, once either
+        /// the outcome can no longer flip regardless of who's left to vote, or the voting
+        /// period has elapsed (in which case the prime validator's vote fills in for every
+        /// validator who abstained or never voted).
+        #[weight = 10_000]
+        pub fn close_operation(origin, operation_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if !Self::approved_validators(&who) {
+                return Err(Error::<T>::NotValidator.into());
+            }
+
+            Self::pending_operations(operation_id).ok_or(Error::<T>::OperationNotFound)?;
+
+            let now = frame_system::Module::<T>::block_number();
+            let period_elapsed = now >= Self::voting_deadline(operation_id);
+
+            let (total_votes, _approve_votes, _deny_votes, total_eligible_weight, turnout_weight,
+                approve_weight, deny_weight) = Self::tally_votes(operation_id);
+
+            // The "outcome can't flip" fast path must still clear the same participation floor
+            // `run_consensus` would otherwise enforce, or a single outsized-weight validator
+            // could force a decision the turnout/minimum-votes gates were meant to block.
+            let turnout = Permill::from_rational(turnout_weight, total_eligible_weight.max(1));
+            let meets_participation_floor =
+                total_votes >= T::MinimumVotes::get() && turnout >= T::TurnoutThreshold::get();
+
+            // However the remaining validators voted (even at max conviction), could
+            // `run_consensus`'s actual bias-adjusted approval check still flip? A flat
+            // majority comparison isn't enough to answer that: the required approval
+            // fraction shrinks or grows with turnout, so a margin that looks safe under plain
+            // majority can still disagree with `run_consensus` once turnout finishes moving.
+            // `turnout_weight` is tracked at base (non-convicted) weight, so the remaining
+            // validators all turning out drives it to exactly `total_eligible_weight` -- which
+            // is also the only turnout value either extreme below needs, since it collapses
+            // the turnout bias to `ApprovalThreshold` exactly. Check both extremes (every bit
+            // of `remaining_weight` landing against the current split, or all of it landing in
+            // its favor); if they agree, nothing in between can disagree either.
+            let remaining_weight = total_eligible_weight
+                .saturating_sub(turnout_weight)
+                .saturating_mul(Self::conviction_multiplier(6));
+            let approved_if_remaining_favors_deny = Self::would_approve(
+                approve_weight,
+                deny_weight.saturating_add(remaining_weight),
+                total_eligible_weight,
+                total_eligible_weight,
+            );
+            let approved_if_remaining_favors_approve = Self::would_approve(
+                approve_weight.saturating_add(remaining_weight),
+                deny_weight,
+                total_eligible_weight,
+                total_eligible_weight,
+            );
+            let decided = meets_participation_floor
+                && approved_if_remaining_favors_deny == approved_if_remaining_favors_approve;
+
+            if !decided && !period_elapsed {
+                return Err(Error::<T>::VotingStillOpen.into());
+            }
+
+            if period_elapsed && !decided {
+                if let Some(prime) = Self::prime_validator() {
+                    if let Some(prime_vote) = Self::operation_votes(operation_id, &prime) {
+                        for (validator, _) in ApprovedValidators::<T>::iter() {
+                            let absent = match Self::operation_votes(operation_id, &validator) {
+                                None => true,
+                                Some(Vote::Abstain) => true,
+                                Some(_) => false,
+                            };
+                            if absent {
+                                OperationVotes::<T>::insert(&operation_id, &validator, &prime_vote);
+                            }
+                        }
+                    }
+                }
+            }
+
+            Self::deposit_event(RawEvent::OperationClosed(operation_id, who));
+
+            Self::run_consensus(operation_id, true)
+        }
+
+        /// Emergency veto from the operation's origin-device owner. Before consensus is
+        /// reached this immediately denies and archives the operation regardless of how
+        /// validators have voted so far; after consensus, while the operation is still
+        /// sitting out its `ExecutionDelay` window, this instead cancels the scheduled
+        /// execution the same way `cancel_operation` does, so device owners actually have a
+        /// veto during the window that delay exists to give them.
+        #[weight = 10_000]
+        pub fn veto_operation(origin, operation_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if let Some(operation) = Self::pending_operations(operation_id) {
+                let owner = Self::device_owners(&operation.origin_device)
+                    .ok_or(Error::<T>::NotDeviceOwner)?;
+                if owner != who {
+                    return Err(Error::<T>::NotDeviceOwner.into());
+                }
+
+                let consensus_result = ConsensusResult {
+                    total_votes: 0,
+                    approve_votes: 0,
+                    deny_votes: 0,
+                    approved: false,
+                    turnout: Permill::zero(),
+                    effective_threshold: Permill::one(),
+                };
+
+                OperationHistory::<T>::insert(&operation_id, (&operation, &consensus_result));
+
+                PendingOperations::<T>::remove(&operation_id);
+                OperationVotes::<T>::remove_prefix(&operation_id);
+                OperationSeed::<T>::remove(&operation_id);
+                CurrentTranche::remove(&operation_id);
+                TrancheOpenedAt::<T>::remove(&operation_id);
+                AssignedTranche::<T>::remove_prefix(&operation_id);
+                VotingDeadline::<T>::remove(&operation_id);
+                VoteConvictions::<T>::remove_prefix(&operation_id);
+                Self::release_preimage(&operation.details_hash);
+
+                Self::deposit_event(RawEvent::OperationVetoed(operation_id, who));
+                Self::deposit_event(RawEvent::ConsensusReached(operation_id, false));
+
+                return Ok(());
+            }
+
+            let (operation, _result) = Self::operation_history(operation_id)
+                .ok_or(Error::<T>::OperationNotFound)?;
+
+            let owner = Self::device_owners(&operation.origin_device)
+                .ok_or(Error::<T>::NotDeviceOwner)?;
+            if owner != who {
+                return Err(Error::<T>::NotDeviceOwner.into());
+            }
+
+            Self::cancel_scheduled_operation(operation_id)?;
+
+            Self::deposit_event(RawEvent::OperationVetoed(operation_id, who));
+
+            Ok(())
+        }
     }
 }
 
 impl<T: Trait> Module<T> {
-    /// Check if consensus is reached for an operation
+    /// Check if consensus is reached for an operation.
+    ///
+    /// Consensus now has to clear two independent, weighted gates instead of a single
+    /// unweighted approval ratio:
+    ///
+    /// 1. A *turnout* gate: the combined weight of every validator who cast a vote (including
+    ///    `Abstain`) must reach `T::TurnoutThreshold` of the total eligible weight, otherwise we
+    ///    keep waiting regardless of how one-sided the votes cast so far are.
+    /// 2. An *approval* gate with a negative turnout bias: the required approval fraction
+    ///    shrinks towards `ApprovalThreshold` as turnout approaches 100% of eligible weight, and
+    ///    grows above it the lower turnout is, following
+    ///    `required = ApprovalThreshold * sqrt(total_eligible_weight) / sqrt(turnout_weight)`.
+    ///    This rewards operations that a large share of the validator set actually weighed in on.
     fn check_consensus(operation_id: u32) -> DispatchResult {
+        Self::run_consensus(operation_id, false)
+    }
+
+    /// Tally votes and, if the gates pass (or `force` skips them because `close_operation`
+    /// found the outcome already settled or the voting period elapsed), finalize the operation.
+    fn run_consensus(operation_id: u32, force: bool) -> DispatchResult {
         let operation = Self::pending_operations(operation_id)
             .ok_or(Error::<T>::OperationNotFound)?;
 
-        // Count votes
-        let mut total_votes = 0u32;
-        let mut approve_votes = 0u32;
-        let mut deny_votes = 0u32;
+        let (total_votes, approve_votes, deny_votes, total_eligible_weight, turnout_weight,
+            approve_weight, deny_weight) = Self::tally_votes(operation_id);
 
-        // Iterate through all validators and their votes
-        for (validator, _) in ApprovedValidators::<T>::iter() {
-            if let Some(vote) = Self::operation_votes(operation_id, &validator) {
-                total_votes = total_votes.saturating_add(1);
-                match vote {
-                    Vote::Approve => approve_votes = approve_votes.saturating_add(1),
-                    Vote::Deny => deny_votes = deny_votes.saturating_add(1),
-                    Vote::Abstain => {}, // Don't count abstentions
-                }
-            }
+        // Check if minimum votes reached (skipped when `close_operation` forces finalization)
+        if !force && total_votes < T::MinimumVotes::get() {
+            return Ok(()); // Not enough votes yet
         }
 
-        // Check if minimum votes reached
-        if total_votes < T::MinimumVotes::get() {
-            return Ok(()); // Not enough votes yet
+        let turnout = Permill::from_rational(turnout_weight, total_eligible_weight.max(1));
+
+        // Turnout gate: don't evaluate approval until enough eligible weight has shown up
+        if !force && turnout < T::TurnoutThreshold::get() {
+            return Ok(());
         }
 
-        // Calculate approval percentage
-        let voting_total = approve_votes.saturating_add(deny_votes);
-        let approval_percentage = if voting_total > 0 {
-            (approve_votes * 100) / voting_total
-        } else {
-            0
-        };
+        let approved = Self::would_approve(approve_weight, deny_weight, turnout_weight, total_eligible_weight);
 
-        let approved = approval_percentage >= T::ApprovalThreshold::get();
+        // Saturates at 100% when the bias pushes the required ratio above 1; a required
+        // ratio that high is already unreachable, so the saturated value is an honest summary.
+        let sqrt_total = Self::isqrt(total_eligible_weight).max(1);
+        let sqrt_turnout = Self::isqrt(turnout_weight).max(1);
+        let base_threshold = T::ApprovalThreshold::get() as u128;
+        let effective_threshold = Permill::from_rational(
+            base_threshold.saturating_mul(sqrt_total),
+            (100u128).saturating_mul(sqrt_turnout),
+        );
 
         let consensus_result = ConsensusResult {
             total_votes,
             approve_votes,
             deny_votes,
             approved,
+            turnout,
+            effective_threshold,
         };
 
         // Store in history
@@ -326,15 +934,187 @@ impl<T: Trait> Module<T> {
         // Clear votes (no longer needed)
         OperationVotes::<T>::remove_prefix(&operation_id);
 
+        // Clear tranche-assignment working state; `NoShows` is kept as part of the audit trail
+        OperationSeed::<T>::remove(&operation_id);
+        CurrentTranche::remove(&operation_id);
+        TrancheOpenedAt::<T>::remove(&operation_id);
+        AssignedTranche::<T>::remove_prefix(&operation_id);
+        VotingDeadline::<T>::remove(&operation_id);
+        VoteConvictions::<T>::remove_prefix(&operation_id);
+
         Self::deposit_event(RawEvent::ConsensusReached(operation_id, approved));
 
         if approved {
-            Self::deposit_event(RawEvent::OperationExecuted(operation_id));
+            if Self::requires_high_consensus(&operation.operation_type) {
+                // Give device owners a mandatory veto window instead of executing inline. The
+                // noted preimage is still referenced until execution, so it's released there
+                // (on_initialize's `ScheduledExecutions` drain) rather than here.
+                let now = frame_system::Module::<T>::block_number();
+                let execute_at = now.saturating_add(T::ExecutionDelay::get());
+                ScheduledExecutions::<T>::try_mutate(&execute_at, |list| list.try_push(operation_id))
+                    .map_err(|_| Error::<T>::TooManyOperationsThisBlock)?;
+                PendingExecution::<T>::insert(&operation_id, execute_at);
+                Self::deposit_event(RawEvent::OperationScheduled(operation_id));
+            } else {
+                Self::release_preimage(&operation.details_hash);
+                Self::deposit_event(RawEvent::OperationExecuted(operation_id));
+            }
+        } else {
+            // Denied operations are done with their preimage too
+            Self::release_preimage(&operation.details_hash);
+        }
+
+        Ok(())
+    }
+
+    /// The bias-adjusted approval test `run_consensus` applies once the turnout gate clears:
+    /// required approval fraction shrinks towards `ApprovalThreshold` as `turnout_weight`
+    /// approaches `total_eligible_weight`, and grows above it the lower turnout is. Factored
+    /// out so `close_operation`'s "can the outcome still flip" fast path can evaluate the same
+    /// threshold against a hypothetical future tally instead of a flat majority comparison.
+    fn would_approve(
+        approve_weight: u128,
+        deny_weight: u128,
+        turnout_weight: u128,
+        total_eligible_weight: u128,
+    ) -> bool {
+        let sqrt_total = Self::isqrt(total_eligible_weight).max(1);
+        let sqrt_turnout = Self::isqrt(turnout_weight).max(1);
+        let base_threshold = T::ApprovalThreshold::get() as u128;
+        let decisive_weight = approve_weight.saturating_add(deny_weight);
+
+        // Cross-multiplied comparison avoids the precision loss (and the "can't express a
+        // fraction above 100%" clamp) that converting the required ratio to a `Permill` first
+        // would introduce:
+        //   approve_weight / decisive_weight >= (base_threshold / 100) * sqrt_total / sqrt_turnout
+        let lhs = approve_weight.saturating_mul(100).saturating_mul(sqrt_turnout);
+        let rhs = base_threshold.saturating_mul(decisive_weight).saturating_mul(sqrt_total);
+        decisive_weight > 0 && lhs >= rhs
+    }
+
+    /// Integer square root (Newton's method), used to apply the negative turnout bias without
+    /// pulling in floating point or a fixed-point sqrt crate.
+    fn isqrt(n: u128) -> u128 {
+        if n == 0 {
+            return 0;
+        }
+        let mut x = n;
+        let mut y = (x + 1) / 2;
+        while y < x {
+            x = y;
+            y = (x + n / x) / 2;
+        }
+        x
+    }
+
+    /// Tally every approved validator's vote on an operation, returning
+    /// `(total_votes, approve_votes, deny_votes, total_eligible_weight, turnout_weight,
+    /// approve_weight, deny_weight)`. Shared by `run_consensus` and `close_operation` so both
+    /// agree on what the vote currently looks like.
+    fn tally_votes(operation_id: u32) -> (u32, u32, u32, u128, u128, u128, u128) {
+        let mut total_votes = 0u32;
+        let mut approve_votes = 0u32;
+        let mut deny_votes = 0u32;
+        let mut total_eligible_weight: u128 = 0;
+        let mut turnout_weight: u128 = 0;
+        let mut approve_weight: u128 = 0;
+        let mut deny_weight: u128 = 0;
+
+        for (validator, _) in ApprovedValidators::<T>::iter() {
+            let weight = Self::voting_weight(&validator) as u128;
+            total_eligible_weight = total_eligible_weight.saturating_add(weight);
+
+            if let Some(vote) = Self::operation_votes(operation_id, &validator) {
+                total_votes = total_votes.saturating_add(1);
+                turnout_weight = turnout_weight.saturating_add(weight);
+
+                // Conviction only amplifies a decisive Approve/Deny; turnout above is tracked
+                // at base weight so locking up for conviction can't also inflate participation.
+                let conviction = Self::vote_convictions(operation_id, &validator);
+                let convicted_weight = weight.saturating_mul(Self::conviction_multiplier(conviction));
+
+                match vote {
+                    Vote::Approve => {
+                        approve_votes = approve_votes.saturating_add(1);
+                        approve_weight = approve_weight.saturating_add(convicted_weight);
+                    },
+                    Vote::Deny => {
+                        deny_votes = deny_votes.saturating_add(1);
+                        deny_weight = deny_weight.saturating_add(convicted_weight);
+                    },
+                    Vote::Abstain => {}, // Counts toward turnout, not approval
+                }
+            }
+        }
+
+        (total_votes, approve_votes, deny_votes, total_eligible_weight, turnout_weight,
+            approve_weight, deny_weight)
+    }
+
+    /// Cancel a scheduled-but-unexecuted operation's execution, shared by `cancel_operation`
+    /// (governance) and `veto_operation`'s post-consensus path (device owner); the caller is
+    /// responsible for authorizing the request before calling this.
+    fn cancel_scheduled_operation(operation_id: u32) -> DispatchResult {
+        PendingExecution::<T>::get(operation_id).ok_or(Error::<T>::OperationNotScheduled)?;
+
+        if Self::vetoed(operation_id) {
+            return Err(Error::<T>::AlreadyVetoed.into());
         }
 
+        Vetoed::insert(&operation_id, true);
+
         Ok(())
     }
 
+    /// Release an operation's reference on a noted preimage now that the operation has reached
+    /// a terminal state (denied, executed, cancelled, or vetoed), dropping the preimage once no
+    /// operation references it anymore.
+    fn release_preimage(details_hash: &T::Hash) {
+        PreimageRequestCount::<T>::mutate(details_hash, |count| {
+            *count = count.saturating_sub(1)
+        });
+        if Self::preimage_request_count(details_hash) == 0 {
+            Preimages::<T>::remove(details_hash);
+        }
+    }
+
+    /// Weight multiplier for a conviction level (0-6): `1, 1, 2, 3, 4, 5, 6`
+    fn conviction_multiplier(conviction: u8) -> u128 {
+        (conviction.max(1)) as u128
+    }
+
+    /// Lock period for a conviction level: zero at conviction 0, `VoteLockPeriod` at level 1,
+    /// doubling for each level beyond that
+    fn lock_period_for(conviction: u8) -> T::BlockNumber {
+        if conviction == 0 {
+            return Zero::zero();
+        }
+
+        let mut period = T::VoteLockPeriod::get();
+        for _ in 1..conviction {
+            period = period.saturating_add(period);
+        }
+        period
+    }
+
+    /// Compute (or fetch the cached) delay tranche a validator is assigned to for an
+    /// operation, by hashing the operation's randomness seed together with the validator's
+    /// account id. The result is cached in `AssignedTranche` so it only needs computing once
+    /// and is verifiable on-chain afterwards.
+    fn assigned_tranche_of(operation_id: u32, validator: &T::AccountId) -> u32 {
+        if let Some(tranche) = Self::assigned_tranche(operation_id, validator) {
+            return tranche;
+        }
+
+        let seed = Self::operation_seed(operation_id);
+        let hash = (seed, validator).using_encoded(blake2_256);
+        let assignment_value = u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]]);
+        let tranche = assignment_value % T::MaxTranches::get().max(1);
+
+        AssignedTranche::<T>::insert(operation_id, validator, tranche);
+        tranche
+    }
+
     /// Get consensus result for an operation
     pub fn get_consensus_result(operation_id: u32) -> Option<bool> {
         Self::operation_history(operation_id).map(|(_, result)| result.approved)
@@ -356,8 +1136,7 @@ impl<T: Trait> Module<T> {
 mod tests {
     use super::*;
     use frame_support::{
-        assert_ok, assert_noop, impl_outer_origin, parameter_types, weights::Weight,
-        traits::{OnInitialize, OnFinalize}
+        assert_noop, assert_ok, impl_outer_origin, impl_outer_event, parameter_types, weights::Weight,
     };
     use sp_core::H256;
     use sp_runtime::{
@@ -365,10 +1144,762 @@ mod tests {
     };
     use frame_system as system;
 
-    // Test that operations can be submitted and voted on
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    mod pallet_ubuntu_os {
+        pub use crate::Event;
+    }
+
+    impl_outer_event! {
+        pub enum TestEvent for Test {
+            system<T>,
+            pallet_ubuntu_os<T>,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+
+    impl system::Trait for Test {
+        type BaseCallFilter = ();
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type Event = TestEvent;
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type DbWeight = ();
+        type BlockExecutionWeight = ();
+        type ExtrinsicBaseWeight = ();
+        type MaximumExtrinsicWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+        type PalletInfo = ();
+        type AccountData = ();
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type SystemWeightInfo = ();
+    }
+
+    /// Deterministic stand-in for on-chain randomness: just hashes the subject, so tests
+    /// don't depend on a real VRF/BABE source.
+    pub struct TestRandomness;
+    impl Randomness<H256, u64> for TestRandomness {
+        fn random(subject: &[u8]) -> (H256, u64) {
+            (H256::from(blake2_256(subject)), system::Module::<Test>::block_number())
+        }
+    }
+
+    parameter_types! {
+        pub const MinimumVotes: u32 = 1;
+        pub const ApprovalThreshold: u32 = 50;
+        pub const TurnoutThreshold: Permill = Permill::from_percent(50);
+        // A single tranche means every validator is assigned to tranche 0, so tests don't
+        // depend on how the VRF-derived seed happens to spread validators across tranches.
+        pub const MaxTranches: u32 = 1;
+        pub const MaxDetailsLength: u32 = 256;
+        pub const MaxOperationsPerBlock: u32 = 16;
+        pub const MaxValidators: u32 = 16;
+        pub const NoShowDelay: u64 = 5;
+        pub const ExecutionDelay: u64 = 3;
+        pub const VoteLockPeriod: u64 = 10;
+        pub const VotingPeriod: u64 = 20;
+    }
+
+    impl Trait for Test {
+        type Event = TestEvent;
+        type VotingOrigin = system::EnsureRoot<u64>;
+        type MinimumVotes = MinimumVotes;
+        type ApprovalThreshold = ApprovalThreshold;
+        type TurnoutThreshold = TurnoutThreshold;
+        type Randomness = TestRandomness;
+        type MaxTranches = MaxTranches;
+        type MaxDetailsLength = MaxDetailsLength;
+        type MaxOperationsPerBlock = MaxOperationsPerBlock;
+        type MaxValidators = MaxValidators;
+        type NoShowDelay = NoShowDelay;
+        type ExecutionDelay = ExecutionDelay;
+        type VoteLockPeriod = VoteLockPeriod;
+        type VotingPeriod = VotingPeriod;
+    }
+
+    type UbuntuOS = Module<Test>;
+    type System = system::Module<Test>;
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+    }
+
+    fn note_and_submit(who: u64, details: &[u8], origin_device: &[u8]) -> u32 {
+        assert_ok!(UbuntuOS::note_details_preimage(Origin::signed(who), details.to_vec()));
+        let operation_id = NextOperationId::get();
+        assert_ok!(UbuntuOS::submit_operation(
+            Origin::signed(who),
+            OperationType::Process,
+            BlakeTwo256::hash(details),
+            origin_device.to_vec(),
+        ));
+        operation_id
+    }
+
+    // chunk1-1: turnout must clear TurnoutThreshold before the weighted approval gate is
+    // even evaluated, regardless of how lopsided the votes cast so far already are.
+    #[test]
+    fn turnout_gate_blocks_consensus_below_threshold() {
+        new_test_ext().execute_with(|| {
+            for validator in 1u64..=4 {
+                assert_ok!(UbuntuOS::add_validator(Origin::signed(100), validator));
+            }
+
+            let operation_id = note_and_submit(1, b"details", b"device");
+
+            // One of four equally-weighted validators approving is only 25% turnout.
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(1), operation_id, Vote::Approve, 0));
+            assert!(UbuntuOS::pending_operations(operation_id).is_some());
+            assert_eq!(UbuntuOS::get_consensus_result(operation_id), None);
+
+            // A second approval brings turnout to 50%, clearing TurnoutThreshold; the
+            // negative turnout bias then still lets the unanimous decisive vote pass.
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(2), operation_id, Vote::Approve, 0));
+            assert!(UbuntuOS::pending_operations(operation_id).is_none());
+            assert_eq!(UbuntuOS::get_consensus_result(operation_id), Some(true));
+        });
+    }
+
+    // chunk1-5: close_operation falls back to the prime validator's vote for absentees once
+    // the voting period elapses, and veto_operation lets the origin device's owner archive an
+    // operation outright regardless of how validators have voted.
+    #[test]
+    fn close_operation_uses_prime_fallback_and_veto_overrides_validators() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 1));
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 2));
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 3));
+            assert_ok!(UbuntuOS::set_prime_validator(system::RawOrigin::Root.into(), 1));
+
+            let operation_id = note_and_submit(1, b"prime-fallback", b"device-a");
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(1), operation_id, Vote::Approve, 0));
+
+            // Only one of three validators has voted (33% turnout, below TurnoutThreshold), so
+            // the operation is still pending until the voting period elapses.
+            assert!(UbuntuOS::pending_operations(operation_id).is_some());
+
+            // Validators 2 and 3 never vote; advance past the voting period so close_operation
+            // may force a decision, filling in the prime validator's vote for both absentees.
+            System::set_block_number(System::block_number() + VotingPeriod::get());
+            assert_ok!(UbuntuOS::close_operation(Origin::signed(2), operation_id));
+            assert_eq!(UbuntuOS::get_consensus_result(operation_id), Some(true));
+
+            // A separate operation, vetoed outright by its origin device's registered owner.
+            assert_ok!(UbuntuOS::register_device(system::RawOrigin::Root.into(), b"device-b".to_vec(), 99));
+            let vetoed_id = note_and_submit(1, b"vetoed", b"device-b");
+            assert_ok!(UbuntuOS::veto_operation(Origin::signed(99), vetoed_id));
+            assert_eq!(UbuntuOS::get_consensus_result(vetoed_id), Some(false));
+        });
+    }
+
+    // chunk1-5: veto_operation only covered the pre-consensus window; once a high-consensus
+    // operation clears voting it moves into ScheduledExecutions awaiting ExecutionDelay, where
+    // the device owner had no way to veto it at all. This covers that the owner can still veto
+    // during that window, and that a non-owner and a not-actually-scheduled id are rejected.
+    #[test]
+    fn veto_operation_covers_the_post_consensus_execution_delay_window() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 1));
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 2));
+            // A third validator who never votes keeps one approval at 33% turnout, below
+            // TurnoutThreshold, so consensus is only reached on the second approval.
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 3));
+            assert_ok!(UbuntuOS::register_device(system::RawOrigin::Root.into(), b"device-a".to_vec(), 99));
+
+            assert_ok!(UbuntuOS::note_details_preimage(Origin::signed(1), b"reboot".to_vec()));
+            let operation_id = NextOperationId::get();
+            assert_ok!(UbuntuOS::submit_operation(
+                Origin::signed(1),
+                OperationType::Sudo,
+                BlakeTwo256::hash(b"reboot"),
+                b"device-a".to_vec(),
+            ));
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(1), operation_id, Vote::Approve, 0));
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(2), operation_id, Vote::Approve, 0));
+
+            // Consensus has been reached; the operation is scheduled, not in PendingOperations
+            // anymore, so veto_operation must take the post-consensus path to reach it at all.
+            assert!(UbuntuOS::pending_operations(operation_id).is_none());
+            assert_eq!(UbuntuOS::get_consensus_result(operation_id), Some(true));
+
+            // Someone other than the device owner can't veto it.
+            assert_noop!(
+                UbuntuOS::veto_operation(Origin::signed(1), operation_id),
+                Error::<Test>::NotDeviceOwner
+            );
+
+            // An id that was never scheduled (nothing in OperationHistory) is rejected too.
+            assert_noop!(
+                UbuntuOS::veto_operation(Origin::signed(99), 999),
+                Error::<Test>::OperationNotFound
+            );
+
+            assert_ok!(UbuntuOS::veto_operation(Origin::signed(99), operation_id));
+
+            // The veto window still has to run its course through on_initialize before the
+            // preimage is actually released, same as cancel_operation.
+            assert!(UbuntuOS::preimages(BlakeTwo256::hash(b"reboot")).is_some());
+            let execute_at = System::block_number() + ExecutionDelay::get();
+            <UbuntuOS as frame_support::traits::OnInitialize<u64>>::on_initialize(execute_at);
+            assert!(UbuntuOS::preimages(BlakeTwo256::hash(b"reboot")).is_none());
+        });
+    }
+
+    // chunk1-2: the no-show scan only visits operations whose no-show window is actually due
+    // this block (via `NoShowCheckAt`), rather than walking every `PendingOperations` entry
+    // on every block regardless of whether its window has elapsed.
+    #[test]
+    fn no_show_scan_only_fires_at_the_operations_scheduled_check_block() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 1));
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 2));
+            // Weight 2 for validator 2 keeps validator 1's lone approval at 1/3 turnout, below
+            // TurnoutThreshold, so the operation stays pending for the no-show scan.
+            assert_ok!(UbuntuOS::set_voting_weight(system::RawOrigin::Root.into(), 2, 2));
+
+            let operation_id = note_and_submit(1, b"details", b"device");
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(1), operation_id, Vote::Approve, 0));
+            // Validator 2 never votes.
+
+            let due = System::block_number() + NoShowDelay::get();
+
+            // Running on_initialize before the no-show window elapses must not flag anyone yet.
+            <UbuntuOS as frame_support::traits::OnInitialize<u64>>::on_initialize(due - 1);
+            assert!(UbuntuOS::no_shows(operation_id).is_empty());
+            assert_eq!(UbuntuOS::current_tranche(operation_id), 0);
+
+            // At the scheduled check block, the absent validator is recorded and the next
+            // tranche opens.
+            <UbuntuOS as frame_support::traits::OnInitialize<u64>>::on_initialize(due);
+            assert_eq!(UbuntuOS::no_shows(operation_id).into_inner(), vec![2u64]);
+            assert_eq!(UbuntuOS::current_tranche(operation_id), 1);
+        });
+    }
+
+    // chunk1-2: submit_operation is open to any signed account, so without a bound repeated
+    // calls could pile an unbounded number of operations onto a single block's NoShowCheckAt
+    // entry. MaxOperationsPerBlock caps that, the same way the sibling pallet's
+    // MaxTransactionsPerBlock caps PendingDeadlines.
+    #[test]
+    fn submit_operation_is_bounded_by_max_operations_per_block() {
+        new_test_ext().execute_with(|| {
+            for i in 0..MaxOperationsPerBlock::get() {
+                assert_ok!(UbuntuOS::note_details_preimage(Origin::signed(1), b"details".to_vec()));
+                assert_ok!(UbuntuOS::submit_operation(
+                    Origin::signed(1),
+                    OperationType::Process,
+                    BlakeTwo256::hash(b"details"),
+                    i.encode(),
+                ));
+            }
+
+            assert_noop!(
+                UbuntuOS::submit_operation(
+                    Origin::signed(1),
+                    OperationType::Process,
+                    BlakeTwo256::hash(b"details"),
+                    b"one-too-many".to_vec(),
+                ),
+                Error::<Test>::TooManyOperationsThisBlock
+            );
+        });
+    }
+
+    // chunk1-2: NoShows is bounded by MaxValidators, which only holds if ApprovedValidators
+    // itself can't grow past that bound -- so add_validator has to refuse once MaxValidators
+    // approved validators are already registered.
+    #[test]
+    fn add_validator_is_bounded_by_max_validators() {
+        new_test_ext().execute_with(|| {
+            for i in 0..MaxValidators::get() as u64 {
+                assert_ok!(UbuntuOS::add_validator(Origin::signed(100), i));
+            }
+
+            assert_noop!(
+                UbuntuOS::add_validator(Origin::signed(100), MaxValidators::get() as u64),
+                Error::<Test>::TooManyValidators
+            );
+
+            // Re-adding an already-approved validator doesn't count against the bound.
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 0));
+        });
+    }
+
+    // chunk1-3: a high-consensus operation that clears voting doesn't execute inline — it
+    // waits out ExecutionDelay behind ScheduledExecutions first, and cancel_operation vetoes
+    // it during that window instead of letting it run.
+    #[test]
+    fn timelocked_execution_drains_after_delay_and_cancel_vetoes_before_it_runs() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 1));
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 2));
+            // A third validator who never votes keeps one approval below TurnoutThreshold, so
+            // each operation below only clears consensus on its second approval.
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 3));
+
+            // Sudo requires high consensus, so approval schedules execution instead of
+            // running immediately.
+            assert_ok!(UbuntuOS::note_details_preimage(Origin::signed(1), b"reboot".to_vec()));
+            let operation_id = NextOperationId::get();
+            assert_ok!(UbuntuOS::submit_operation(
+                Origin::signed(1),
+                OperationType::Sudo,
+                BlakeTwo256::hash(b"reboot"),
+                b"device".to_vec(),
+            ));
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(1), operation_id, Vote::Approve, 0));
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(2), operation_id, Vote::Approve, 0));
+
+            assert_eq!(UbuntuOS::get_consensus_result(operation_id), Some(true));
+            // Preimage still referenced: the operation is scheduled, not executed yet.
+            assert!(UbuntuOS::preimages(BlakeTwo256::hash(b"reboot")).is_some());
+
+            let execute_at = System::block_number() + ExecutionDelay::get();
+            <UbuntuOS as frame_support::traits::OnInitialize<u64>>::on_initialize(execute_at);
+            assert!(UbuntuOS::preimages(BlakeTwo256::hash(b"reboot")).is_none());
+
+            // A second operation, cancelled (vetoed) by governance before its execution runs.
+            assert_ok!(UbuntuOS::note_details_preimage(Origin::signed(1), b"wipe".to_vec()));
+            let vetoed_id = NextOperationId::get();
+            assert_ok!(UbuntuOS::submit_operation(
+                Origin::signed(1),
+                OperationType::Sudo,
+                BlakeTwo256::hash(b"wipe"),
+                b"device".to_vec(),
+            ));
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(1), vetoed_id, Vote::Approve, 0));
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(2), vetoed_id, Vote::Approve, 0));
+            assert_eq!(UbuntuOS::get_consensus_result(vetoed_id), Some(true));
+
+            assert_ok!(UbuntuOS::cancel_operation(system::RawOrigin::Root.into(), vetoed_id));
+            let veto_execute_at = System::block_number() + ExecutionDelay::get();
+            <UbuntuOS as frame_support::traits::OnInitialize<u64>>::on_initialize(veto_execute_at);
+            assert!(UbuntuOS::preimages(BlakeTwo256::hash(b"wipe")).is_none());
+        });
+    }
+
+    // chunk1-3: a high-consensus operation's approval schedules it against ScheduledExecutions,
+    // the same unbounded-per-block structure as NoShowCheckAt; MaxOperationsPerBlock caps it
+    // too, so a flood of high-consensus approvals landing on the same execute_at block can't
+    // grow that entry without limit.
+    #[test]
+    fn scheduled_executions_is_bounded_by_max_operations_per_block() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 1));
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 2));
+            // A third validator who never votes keeps one approval below TurnoutThreshold, so
+            // each operation below only clears consensus on its second approval.
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 3));
+
+            // Submitted one block before the others, so it doesn't share their NoShowCheckAt
+            // entry (bounded by MaxOperationsPerBlock too); one approval leaves it pending.
+            assert_ok!(UbuntuOS::note_details_preimage(Origin::signed(1), b"details".to_vec()));
+            let one_too_many = NextOperationId::get();
+            assert_ok!(UbuntuOS::submit_operation(
+                Origin::signed(1),
+                OperationType::Sudo,
+                BlakeTwo256::hash(b"details"),
+                b"one-too-many".to_vec(),
+            ));
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(1), one_too_many, Vote::Approve, 0));
+            System::set_block_number(System::block_number() + 1);
+
+            for i in 0..MaxOperationsPerBlock::get() {
+                assert_ok!(UbuntuOS::note_details_preimage(Origin::signed(1), b"details".to_vec()));
+                let operation_id = NextOperationId::get();
+                assert_ok!(UbuntuOS::submit_operation(
+                    Origin::signed(1),
+                    OperationType::Sudo,
+                    BlakeTwo256::hash(b"details"),
+                    i.encode(),
+                ));
+                assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(1), operation_id, Vote::Approve, 0));
+                assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(2), operation_id, Vote::Approve, 0));
+            }
+
+            // Its second approval lands in the same block as the others', on a full entry.
+            assert_noop!(
+                UbuntuOS::vote_on_operation(Origin::signed(2), one_too_many, Vote::Approve, 0),
+                Error::<Test>::TooManyOperationsThisBlock
+            );
+        });
+    }
+
+    // chunk1-3: note_details_preimage rejects a payload over MaxDetailsLength, and a noted
+    // preimage that nothing ever references can be reclaimed via unnote_details_preimage --
+    // but only once its refcount actually hits zero.
+    #[test]
+    fn note_details_preimage_is_bounded_and_unnote_reclaims_unreferenced_entries() {
+        new_test_ext().execute_with(|| {
+            let oversized = vec![0u8; MaxDetailsLength::get() as usize + 1];
+            assert_noop!(
+                UbuntuOS::note_details_preimage(Origin::signed(1), oversized),
+                Error::<Test>::DetailsTooLong
+            );
+
+            assert_ok!(UbuntuOS::note_details_preimage(Origin::signed(1), b"details".to_vec()));
+            let hash = BlakeTwo256::hash(b"details");
+
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 1));
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 2));
+            // A third validator who never votes keeps one approval below TurnoutThreshold, so
+            // consensus is only reached on the second approval.
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 3));
+            // Sudo requires high consensus, so approval schedules execution behind
+            // ExecutionDelay instead of releasing the preimage inline.
+            let operation_id = NextOperationId::get();
+            assert_ok!(UbuntuOS::submit_operation(
+                Origin::signed(1),
+                OperationType::Sudo,
+                hash,
+                b"device".to_vec(),
+            ));
+
+            // Still referenced by the pending operation, so unnote refuses.
+            assert_noop!(
+                UbuntuOS::unnote_details_preimage(Origin::signed(1), hash),
+                Error::<Test>::PreimageStillReferenced
+            );
+
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(1), operation_id, Vote::Approve, 0));
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(2), operation_id, Vote::Approve, 0));
+            assert_eq!(UbuntuOS::get_consensus_result(operation_id), Some(true));
+            // Still referenced: scheduled, not executed yet.
+            assert_noop!(
+                UbuntuOS::unnote_details_preimage(Origin::signed(1), hash),
+                Error::<Test>::PreimageStillReferenced
+            );
+            let execute_at = System::block_number() + ExecutionDelay::get();
+            <UbuntuOS as frame_support::traits::OnInitialize<u64>>::on_initialize(execute_at);
+
+            // Execution released the operation's reference, so the preimage is already gone.
+            assert_noop!(
+                UbuntuOS::unnote_details_preimage(Origin::signed(1), hash),
+                Error::<Test>::PreimageNotFound
+            );
+
+            // A second, never-submitted preimage has a refcount of zero from the start, and
+            // can be unnoted straight away.
+            assert_ok!(UbuntuOS::note_details_preimage(Origin::signed(1), b"unused".to_vec()));
+            let unused_hash = BlakeTwo256::hash(b"unused");
+            assert_ok!(UbuntuOS::unnote_details_preimage(Origin::signed(1), unused_hash));
+            assert!(UbuntuOS::preimages(unused_hash).is_none());
+        });
+    }
+
+    // chunk1-4: a conviction-weighted vote locks the validator's voting weight until the lock
+    // expires, so set_voting_weight can't reduce it mid-lock and unlock refuses early.
     #[test]
-    fn submit_and_vote_works() {
-        // Implementation of basic test
-        // This would test the full flow of operation submission and voting
+    fn conviction_vote_locks_voting_weight_until_it_expires() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 1));
+            assert_ok!(UbuntuOS::add_validator(Origin::signed(100), 2));
+
+            let operation_id = note_and_submit(1, b"details", b"device");
+            // Conviction level 2 locks for VoteLockPeriod * 2 = 20 blocks.
+            assert_ok!(UbuntuOS::vote_on_operation(Origin::signed(1), operation_id, Vote::Approve, 2));
+
+            assert_noop!(UbuntuOS::unlock(Origin::signed(1)), Error::<Test>::StillLocked);
+            assert_noop!(
+                UbuntuOS::set_voting_weight(system::RawOrigin::Root.into(), 1, 0),
+                Error::<Test>::VoteLocked
+            );
+            // Raising weight is never blocked by the lock.
+            assert_ok!(UbuntuOS::set_voting_weight(system::RawOrigin::Root.into(), 1, 5));
+
+            System::set_block_number(System::block_number() + VoteLockPeriod::get() * 2);
+            assert_ok!(UbuntuOS::unlock(Origin::signed(1)));
+            assert_ok!(UbuntuOS::set_voting_weight(system::RawOrigin::Root.into(), 1, 0));
+        });
+    }
+
+    /// A second mock runtime, identical to [`Test`] except `MaxTranches == 2` (instead of 1),
+    /// so a validator can actually be assigned to a tranche later than 0 — the default mock's
+    /// single tranche never exercises that path at all.
+    mod multi_tranche {
+        use super::*;
+
+        impl_outer_origin! {
+            pub enum Origin for Test2 {}
+        }
+
+        mod pallet_ubuntu_os2 {
+            pub use crate::Event;
+        }
+
+        impl_outer_event! {
+            pub enum TestEvent2 for Test2 {
+                system<T>,
+                pallet_ubuntu_os2<T>,
+            }
+        }
+
+        #[derive(Clone, Eq, PartialEq)]
+        pub struct Test2;
+
+        impl system::Trait for Test2 {
+            type BaseCallFilter = ();
+            type Origin = Origin;
+            type Call = ();
+            type Index = u64;
+            type BlockNumber = u64;
+            type Hash = H256;
+            type Hashing = BlakeTwo256;
+            type AccountId = u64;
+            type Lookup = IdentityLookup<Self::AccountId>;
+            type Header = Header;
+            type Event = TestEvent2;
+            type BlockHashCount = BlockHashCount;
+            type MaximumBlockWeight = MaximumBlockWeight;
+            type DbWeight = ();
+            type BlockExecutionWeight = ();
+            type ExtrinsicBaseWeight = ();
+            type MaximumExtrinsicWeight = MaximumBlockWeight;
+            type MaximumBlockLength = MaximumBlockLength;
+            type AvailableBlockRatio = AvailableBlockRatio;
+            type Version = ();
+            type PalletInfo = ();
+            type AccountData = ();
+            type OnNewAccount = ();
+            type OnKilledAccount = ();
+            type SystemWeightInfo = ();
+        }
+
+        parameter_types! {
+            pub const MaxTranches2: u32 = 2;
+        }
+
+        impl Trait for Test2 {
+            type Event = TestEvent2;
+            type VotingOrigin = system::EnsureRoot<u64>;
+            type MinimumVotes = MinimumVotes;
+            type ApprovalThreshold = ApprovalThreshold;
+            type TurnoutThreshold = TurnoutThreshold;
+            type Randomness = TestRandomness;
+            type MaxTranches = MaxTranches2;
+            type MaxDetailsLength = MaxDetailsLength;
+            type MaxOperationsPerBlock = MaxOperationsPerBlock;
+            type MaxValidators = MaxValidators;
+            type NoShowDelay = NoShowDelay;
+            type ExecutionDelay = ExecutionDelay;
+            type VoteLockPeriod = VoteLockPeriod;
+            type VotingPeriod = VotingPeriod;
+        }
+
+        type UbuntuOS2 = Module<Test2>;
+        type System2 = system::Module<Test2>;
+
+        fn new_test_ext2() -> sp_io::TestExternalities {
+            system::GenesisConfig::default().build_storage::<Test2>().unwrap().into()
+        }
+
+        fn note_and_submit2(who: u64, details: &[u8], origin_device: &[u8]) -> u32 {
+            assert_ok!(UbuntuOS2::note_details_preimage(Origin::signed(who), details.to_vec()));
+            let operation_id = NextOperationId::get();
+            assert_ok!(UbuntuOS2::submit_operation(
+                Origin::signed(who),
+                OperationType::Process,
+                BlakeTwo256::hash(details),
+                origin_device.to_vec(),
+            ));
+            operation_id
+        }
+
+        // chunk1-2: MaxTranches == 1 in the default mock means every validator lands in
+        // tranche 0, so no test ever exercised a validator recruited into a later tranche
+        // after a no-show, or the NotYetAssigned gate blocking them beforehand. This pins
+        // assignments via AssignedTranche (the same cache the pallet itself writes through)
+        // so the escalation path is exercised deterministically rather than by VRF-hash luck.
+        #[test]
+        fn no_show_escalation_opens_the_next_tranche_for_its_assigned_validator() {
+            new_test_ext2().execute_with(|| {
+                assert_ok!(UbuntuOS2::add_validator(Origin::signed(100), 1));
+                assert_ok!(UbuntuOS2::add_validator(Origin::signed(100), 2));
+
+                let operation_id = note_and_submit2(1, b"details", b"device");
+
+                AssignedTranche::<Test2>::insert(operation_id, 1u64, 0u32);
+                AssignedTranche::<Test2>::insert(operation_id, 2u64, 1u32);
+
+                // Validator 2's tranche hasn't opened yet.
+                assert_noop!(
+                    UbuntuOS2::vote_on_operation(Origin::signed(2), operation_id, Vote::Approve, 0),
+                    Error::<Test2>::NotYetAssigned
+                );
+
+                // Validator 1 no-shows in tranche 0, escalating CurrentTranche to 1.
+                let due = System2::block_number() + NoShowDelay::get();
+                <UbuntuOS2 as frame_support::traits::OnInitialize<u64>>::on_initialize(due);
+                assert_eq!(UbuntuOS2::current_tranche(operation_id), 1);
+                assert_eq!(UbuntuOS2::no_shows(operation_id).into_inner(), vec![1u64]);
+
+                // Validator 2's tranche is open now.
+                assert_ok!(UbuntuOS2::vote_on_operation(Origin::signed(2), operation_id, Vote::Approve, 0));
+            });
+        }
+    }
+
+    /// A third mock runtime, identical to [`Test`] except `ApprovalThreshold == 66` (instead of
+    /// 50), so `close_operation`'s "can the outcome still flip" fast path can be pinned against
+    /// a threshold where a flat 50% majority margin and the real configured threshold disagree.
+    mod weighted_threshold {
+        use super::*;
+
+        impl_outer_origin! {
+            pub enum Origin for Test3 {}
+        }
+
+        mod pallet_ubuntu_os3 {
+            pub use crate::Event;
+        }
+
+        impl_outer_event! {
+            pub enum TestEvent3 for Test3 {
+                system<T>,
+                pallet_ubuntu_os3<T>,
+            }
+        }
+
+        #[derive(Clone, Eq, PartialEq)]
+        pub struct Test3;
+
+        impl system::Trait for Test3 {
+            type BaseCallFilter = ();
+            type Origin = Origin;
+            type Call = ();
+            type Index = u64;
+            type BlockNumber = u64;
+            type Hash = H256;
+            type Hashing = BlakeTwo256;
+            type AccountId = u64;
+            type Lookup = IdentityLookup<Self::AccountId>;
+            type Header = Header;
+            type Event = TestEvent3;
+            type BlockHashCount = BlockHashCount;
+            type MaximumBlockWeight = MaximumBlockWeight;
+            type DbWeight = ();
+            type BlockExecutionWeight = ();
+            type ExtrinsicBaseWeight = ();
+            type MaximumExtrinsicWeight = MaximumBlockWeight;
+            type MaximumBlockLength = MaximumBlockLength;
+            type AvailableBlockRatio = AvailableBlockRatio;
+            type Version = ();
+            type PalletInfo = ();
+            type AccountData = ();
+            type OnNewAccount = ();
+            type OnKilledAccount = ();
+            type SystemWeightInfo = ();
+        }
+
+        parameter_types! {
+            pub const ApprovalThreshold3: u32 = 66;
+        }
+
+        impl Trait for Test3 {
+            type Event = TestEvent3;
+            type VotingOrigin = system::EnsureRoot<u64>;
+            type MinimumVotes = MinimumVotes;
+            type ApprovalThreshold = ApprovalThreshold3;
+            type TurnoutThreshold = TurnoutThreshold;
+            type Randomness = TestRandomness;
+            type MaxTranches = MaxTranches;
+            type MaxDetailsLength = MaxDetailsLength;
+            type MaxOperationsPerBlock = MaxOperationsPerBlock;
+            type MaxValidators = MaxValidators;
+            type NoShowDelay = NoShowDelay;
+            type ExecutionDelay = ExecutionDelay;
+            type VoteLockPeriod = VoteLockPeriod;
+            type VotingPeriod = VotingPeriod;
+        }
+
+        type UbuntuOS3 = Module<Test3>;
+
+        fn new_test_ext3() -> sp_io::TestExternalities {
+            system::GenesisConfig::default().build_storage::<Test3>().unwrap().into()
+        }
+
+        fn note_and_submit3(who: u64, details: &[u8], origin_device: &[u8]) -> u32 {
+            assert_ok!(UbuntuOS3::note_details_preimage(Origin::signed(who), details.to_vec()));
+            let operation_id = NextOperationId::get();
+            assert_ok!(UbuntuOS3::submit_operation(
+                Origin::signed(who),
+                OperationType::Process,
+                BlakeTwo256::hash(details),
+                origin_device.to_vec(),
+            ));
+            operation_id
+        }
+
+        // chunk1-5: close_operation's old "decided" fast path compared `approve_weight` against
+        // a flat `deny_weight + remaining_weight` margin, which is really an implicit 50%
+        // majority check -- it ignores `ApprovalThreshold` entirely. Here `ApprovalThreshold` is
+        // 66%, a heavy validator (weight 6) approves, turnout hasn't cleared the gate yet, and a
+        // governance reweight (rather than a new vote) retroactively crosses the turnout gate by
+        // shrinking a non-voting validator's weight to 0. The flat check sees approve_weight (6)
+        // tie the worst-case remaining margin (6) and would call it "decided" and force an
+        // immediate approval; the fixed check instead evaluates the same bias-adjusted threshold
+        // `run_consensus` uses and finds the two extreme outcomes (remaining weight all going to
+        // deny vs. all going to approve) disagree, so it correctly keeps waiting. Letting the
+        // last validator actually vote Deny at max conviction then settles it as denied --
+        // confirming the old fast path would have locked in the wrong answer.
+        #[test]
+        fn close_operation_decided_check_matches_run_consensus_bias_instead_of_flat_majority() {
+            new_test_ext3().execute_with(|| {
+                assert_ok!(UbuntuOS3::add_validator(Origin::signed(100), 1)); // heavy approver
+                assert_ok!(UbuntuOS3::add_validator(Origin::signed(100), 2)); // never votes
+                assert_ok!(UbuntuOS3::add_validator(Origin::signed(100), 3)); // zeroed out later
+
+                assert_ok!(UbuntuOS3::set_voting_weight(system::RawOrigin::Root.into(), 1, 6));
+                assert_ok!(UbuntuOS3::set_voting_weight(system::RawOrigin::Root.into(), 3, 8));
+
+                let operation_id = note_and_submit3(1, b"details", b"device");
+
+                // Validator 1 approves; with validator 3 still weighing 8, turnout is only
+                // 6 / 15 = 40%, below TurnoutThreshold, so this doesn't auto-finalize.
+                assert_ok!(UbuntuOS3::vote_on_operation(Origin::signed(1), operation_id, Vote::Approve, 0));
+                assert!(UbuntuOS3::pending_operations(operation_id).is_some());
+
+                // Governance zeroes out validator 3's weight (never voted, so unlocked). This
+                // retroactively crosses the turnout gate (6 / 7 = 85.7%) without a new vote, so
+                // nothing re-runs check_consensus -- the operation just sits pending until
+                // something calls close_operation.
+                assert_ok!(UbuntuOS3::set_voting_weight(system::RawOrigin::Root.into(), 3, 0));
+
+                // close_operation must NOT treat this as decided: validator 2's vote could still
+                // flip the bias-adjusted outcome (it only ties the old flat 50% margin).
+                assert_noop!(
+                    UbuntuOS3::close_operation(Origin::signed(1), operation_id),
+                    Error::<Test3>::VotingStillOpen
+                );
+
+                // Validator 2 denies at max conviction; the real bias-adjusted consensus check
+                // now resolves the operation as denied, confirming the old flat check would have
+                // forced the wrong (approved) answer had it fired early.
+                assert_ok!(UbuntuOS3::vote_on_operation(Origin::signed(2), operation_id, Vote::Deny, 6));
+                assert_eq!(UbuntuOS3::get_consensus_result(operation_id), Some(false));
+            });
+        }
     }
 }
\ No newline at end of file
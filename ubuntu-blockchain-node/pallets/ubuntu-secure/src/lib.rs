@@ -16,6 +16,53 @@
 //!
 //! Every security-critical operation (file writes, process execution, permission changes)
 //! becomes a blockchain transaction requiring distributed consensus before execution.
+//!
+//! ## VRF-based validator assignment
+//!
+//! As syscall volume grows, requiring all five registered nodes to vote on every
+//! transaction does not scale. Borrowing the assignment-criteria approach used by
+//! relay-chain approval voting, each node holds a VRF keypair and is assigned to a
+//! "tranche" for a given transaction by hashing a rotating session seed together
+//! with the transaction hash. Tranche 0 nodes are expected to vote first; if they
+//! don't respond within [`Config::TrancheDelay`] blocks, later tranches open up to
+//! the remaining registered nodes so the transaction still reaches quorum.
+//!
+//! ## Pacemaker
+//!
+//! A transaction that never collects enough votes would otherwise block its syscall
+//! forever. A HotStuff-style pacemaker schedules a deadline at submission time and
+//! resets it whenever a new vote arrives; if the deadline elapses first, the
+//! transaction is finalized unilaterally, failing closed (denied) for Class A
+//! security-critical operations.
+//!
+//! ## Governed membership
+//!
+//! Nodes are no longer self-registered. Membership of the five-seat active node
+//! set is curated in the style of pallet-membership/collective: only
+//! [`Config::MembershipOrigin`] (root, or a council majority) may add, remove, or
+//! swap a node, and each `NodeType` slot may be held by a single account at a time.
+//!
+//! ## Attested consensus
+//!
+//! Approving nodes contribute a BLS partial signature over the transaction hash,
+//! which `check_consensus` folds into a `ConsensusProof` once every approving
+//! vote has one. The proof carries each contributing node's account alongside
+//! its partial signature, since the set of approving nodes differs per
+//! transaction and there is no single static group key the signatures could be
+//! combined and checked against. The off-chain enforcement layer can then verify
+//! each partial signature against that node's registered [`NodeBlsKeys`] entry,
+//! which is still far cheaper than replaying the full vote tally.
+//!
+//! `vote_on_transactions_batch` lets a node clearing a backlog submit many votes in
+//! one extrinsic; an individually invalid entry is skipped (and reported via a
+//! `BatchVoteRejected` event) rather than reverting the whole batch.
+//!
+//! ## Weighted quorum
+//!
+//! Votes are weighted by [`NodeReputation`], not counted flatly: a node that keeps
+//! voting against consensus carries less influence over time. Finalization requires
+//! the active set's live weight that voted to clear `T::MinParticipation`, and of
+//! the decisive (approve + deny) weight, `T::ApprovalFraction` must approve.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -39,6 +86,9 @@ pub mod pallet {
 	use frame_system::pallet_prelude::*;
 	use codec::{Decode, Encode};
 	use scale_info::TypeInfo;
+	use sp_core::{bls377, ed25519, sr25519};
+	use sp_core::sr25519::vrf::{VrfInput, VrfSignature};
+	use sp_runtime::Permill;
 
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
@@ -48,6 +98,53 @@ pub mod pallet {
 	pub trait Config: frame_system::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type WeightInfo: WeightInfo;
+
+		/// A VRF output (interpreted as a `u32`) must fall below this value for a node
+		/// to be assigned to tranche 0 on a given transaction.
+		#[pallet::constant]
+		type VrfAssignmentThreshold: Get<u32>;
+
+		/// Number of blocks tranche 0 gets to vote before the next tranche opens up to
+		/// additional registered nodes.
+		#[pallet::constant]
+		type TrancheDelay: Get<Self::BlockNumber>;
+
+		/// Number of blocks a session seed remains valid before it is rotated.
+		#[pallet::constant]
+		type SessionLength: Get<Self::BlockNumber>;
+
+		/// Number of blocks a transaction gets to reach consensus before the pacemaker
+		/// finalizes it unilaterally (fail-closed for Class A operations).
+		#[pallet::constant]
+		type Timeout: Get<Self::BlockNumber>;
+
+		/// Upper bound on how many transaction deadlines can fall in the same block.
+		#[pallet::constant]
+		type MaxTransactionsPerBlock: Get<u32>;
+
+		/// Upper bound on how many entries a single `vote_on_transactions_batch` call may
+		/// carry, so an oversized batch is rejected at SCALE-decode time rather than paying
+		/// full per-entry VRF/signature verification before weight-based fees bound the call.
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+
+		/// Origin allowed to add, remove, or swap members of the active node set
+		/// (root, or a council majority).
+		type MembershipOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Maximum size of the active node set (one slot per `NodeType`).
+		#[pallet::constant]
+		type MaxActiveNodes: Get<u32>;
+
+		/// Fraction of decisive (approve + deny) weight that must vote to approve
+		/// for a transaction to pass. Defaults to two-thirds in a typical deployment.
+		#[pallet::constant]
+		type ApprovalFraction: Get<Permill>;
+
+		/// Minimum fraction of the active set's total reputation weight that must
+		/// have voted (including abstentions) before a result can be finalized.
+		#[pallet::constant]
+		type MinParticipation: Get<Permill>;
 	}
 
 	/// System call transaction types based on Ubuntu Secure classification
@@ -86,6 +183,21 @@ pub mod pallet {
 		Abstain,
 	}
 
+	/// A node's registered signature-verifying key. Nodes may sign with either
+	/// sr25519 or ed25519, matching whichever keypair they provisioned at registration.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub enum VerifyingKey {
+		Sr25519(sr25519::Public),
+		Ed25519(ed25519::Public),
+	}
+
+	/// A signature produced by a node, tagged with the scheme it was produced under.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub enum NodeSignature {
+		Sr25519(sr25519::Signature),
+		Ed25519(ed25519::Signature),
+	}
+
 	/// System call transaction structure
 	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 	pub struct SyscallTransaction<AccountId> {
@@ -94,8 +206,21 @@ pub mod pallet {
 		pub path: Vec<u8>, // File path or executable path
 		pub flags: u32,    // Operation flags
 		pub class: TransactionClass,
+		pub nonce: u64, // Caller-chosen value the signature is taken over, so submission-time
+		                // queuing/re-orgs can't change what was signed
 		pub timestamp: u64,
-		pub signature: Vec<u8>, // Cryptographic signature
+		pub signature: NodeSignature, // Cryptographic signature over (syscall_type, path, flags, nonce)
+	}
+
+	/// A single entry in a [`Pallet::vote_on_transactions_batch`] call, carrying
+	/// everything an individual `vote_on_transaction` call would need.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct BatchVoteEntry<Hash> {
+		pub transaction_hash: Hash,
+		pub vote: Vote,
+		pub reason: Vec<u8>,
+		pub vrf_signature: VrfSignature,
+		pub vote_signature: NodeSignature,
 	}
 
 	/// Node vote record
@@ -116,6 +241,37 @@ pub mod pallet {
 		pub votes_against: u32,
 		pub total_votes: u32,
 		pub threshold_met: bool,
+		/// Sum of voting weight (node reputation) behind Approve votes.
+		pub weighted_for: u64,
+		/// Sum of voting weight (node reputation) behind Deny votes.
+		pub weighted_against: u64,
+		/// Sum of voting weight across every vote cast, including abstentions.
+		pub total_weight: u64,
+	}
+
+	/// Attestation that consensus was reached for a transaction, carrying every approving
+	/// node's partial signature alongside the account it belongs to. The signer set differs
+	/// per transaction, so the proof names its signers rather than assuming a single static
+	/// group key; the off-chain enforcement layer verifies each partial signature against
+	/// that node's registered [`NodeBlsKeys`] entry instead of replaying the full vote tally.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct ConsensusProof<AccountId> {
+		/// Every approving node's partial signature, paired with its account. Bounded by
+		/// the active node set's size, since only a registered node that voted to approve
+		/// can contribute one.
+		pub signatures: Vec<(AccountId, bls377::Signature)>,
+	}
+
+	/// Proof that a node was sortitioned into a tranche for a given transaction,
+	/// carried alongside its vote so the pallet can verify assignment on-chain.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct AssignmentCertificate {
+		/// Raw VRF output bytes, used to derive the tranche number.
+		pub vrf_output: [u8; 32],
+		/// VRF proof over `(session_seed, transaction_hash)`.
+		pub vrf_proof: [u8; 64],
+		/// Tranche the node was assigned to (0 = first responders).
+		pub tranche: u8,
 	}
 
 	/// Storage: Pending system call transactions awaiting consensus
@@ -150,7 +306,8 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
-	/// Storage: Registered nodes in the Ubuntu Secure network
+	/// Storage: Registered nodes in the Ubuntu Secure network. Only ever populated by
+	/// the governed `add_node`/`remove_node`/`swap_node` calls.
 	#[pallet::storage]
 	pub type RegisteredNodes<T: Config> = StorageMap<
 		_,
@@ -160,6 +317,14 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Storage: the deliberately curated committee, one account per `NodeType` slot.
+	#[pallet::storage]
+	pub type ActiveNodeSet<T: Config> = StorageValue<
+		_,
+		BoundedVec<(T::AccountId, NodeType), T::MaxActiveNodes>,
+		ValueQuery,
+	>;
+
 	/// Storage: Node reputation scores (Byzantine fault tolerance)
 	#[pallet::storage]
 	pub type NodeReputation<T: Config> = StorageMap<
@@ -174,6 +339,113 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type OSStateHash<T: Config> = StorageValue<_, T::Hash, OptionQuery>;
 
+	/// Storage: VRF public key registered by each node, used to verify assignment proofs.
+	#[pallet::storage]
+	pub type NodeVrfKeys<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		sr25519::Public,
+		OptionQuery,
+	>;
+
+	/// Storage: signature-verifying key registered by each node, used to authenticate
+	/// submitted syscalls and votes.
+	#[pallet::storage]
+	pub type NodeVerifyKeys<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		VerifyingKey,
+		OptionQuery,
+	>;
+
+	/// Storage: rotating per-session randomness seed, refreshed from a recent block hash.
+	#[pallet::storage]
+	pub type SessionSeed<T: Config> = StorageValue<_, [u8; 32], ValueQuery>;
+
+	/// Storage: block number at which the current session seed was set.
+	#[pallet::storage]
+	pub type SessionSeedSetAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// Storage: block number a transaction was submitted at, used to open later tranches.
+	#[pallet::storage]
+	pub type TransactionSubmittedAt<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	/// Storage: VRF assignment certificates submitted by nodes for a transaction.
+	#[pallet::storage]
+	pub type AssignmentCertificates<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		Blake2_128Concat,
+		T::AccountId,
+		AssignmentCertificate,
+		OptionQuery,
+	>;
+
+	/// Storage: BLS public key registered by each node, used to verify partial
+	/// signatures folded into a transaction's [`ConsensusProof`].
+	#[pallet::storage]
+	pub type NodeBlsKeys<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		bls377::Public,
+		OptionQuery,
+	>;
+
+	/// Storage: partial BLS signatures submitted by approving nodes over a
+	/// transaction hash, awaiting aggregation once the approval threshold is met.
+	#[pallet::storage]
+	pub type PartialSignatures<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		Blake2_128Concat,
+		T::AccountId,
+		bls377::Signature,
+		OptionQuery,
+	>;
+
+	/// Storage: the consensus proof for a finalized transaction.
+	#[pallet::storage]
+	pub type ConsensusProofs<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		ConsensusProof<T::AccountId>,
+		OptionQuery,
+	>;
+
+	/// Storage: transactions whose pacemaker deadline falls in a given block, scanned
+	/// by `on_initialize` so only transactions due this block are visited.
+	#[pallet::storage]
+	pub type PendingDeadlines<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::BlockNumber,
+		BoundedVec<T::Hash, T::MaxTransactionsPerBlock>,
+		ValueQuery,
+	>;
+
+	/// Storage: the block at which each pending transaction's pacemaker deadline falls,
+	/// so a fresh vote can find and reschedule the old entry.
+	#[pallet::storage]
+	pub type TransactionDeadline<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
 	/// Events emitted by Ubuntu Secure pallet
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -219,6 +491,65 @@ pub mod pallet {
 			node_id: T::AccountId,
 			reputation: u32,
 		},
+
+		/// The session seed used for VRF tranche assignment was rotated
+		SessionSeedRotated {
+			new_seed: [u8; 32],
+		},
+
+		/// A node was assigned to a tranche for a transaction
+		NodeAssigned {
+			transaction_hash: T::Hash,
+			node_id: T::AccountId,
+			tranche: u8,
+		},
+
+		/// A node holding an assignment certificate for a transaction had not voted by the
+		/// time the transaction was finalized (by consensus or by pacemaker timeout)
+		NodeNoShow {
+			transaction_hash: T::Hash,
+			node_id: T::AccountId,
+			tranche: u8,
+		},
+
+		/// A transaction's pacemaker deadline elapsed without consensus and was
+		/// finalized unilaterally
+		ConsensusTimedOut {
+			transaction_hash: T::Hash,
+			class: TransactionClass,
+			approved: bool,
+		},
+
+		/// A node was removed from the active node set
+		NodeRemoved {
+			node_id: T::AccountId,
+		},
+
+		/// A node's slot in the active set was handed to a different account
+		NodeSwapped {
+			removed: T::AccountId,
+			added: T::AccountId,
+			node_type: NodeType,
+		},
+
+		/// Partial signatures were collected into a consensus proof
+		ConsensusAttested {
+			transaction_hash: T::Hash,
+			proof: ConsensusProof<T::AccountId>,
+		},
+
+		/// One entry of a batch vote was applied successfully
+		BatchVoteApplied {
+			voter: T::AccountId,
+			transaction_hash: T::Hash,
+		},
+
+		/// One entry of a batch vote was rejected; the rest of the batch still ran
+		BatchVoteRejected {
+			voter: T::AccountId,
+			transaction_hash: T::Hash,
+			error: DispatchError,
+		},
 	}
 
 	/// Errors that can be returned by Ubuntu Secure pallet
@@ -240,24 +571,87 @@ pub mod pallet {
 		OperationDenied,
 		/// Node reputation too low
 		LowReputation,
+		/// Node has not registered a VRF public key
+		NoVrfKey,
+		/// VRF proof failed verification or does not match the claimed tranche
+		InvalidVrfProof,
+		/// Node is not assigned to vote on this transaction in the current tranche
+		NotAssigned,
+		/// Too many transaction deadlines already fall in the target block
+		TooManyPendingTransactions,
+		/// Account is already a member of the active node set
+		AlreadyActiveNode,
+		/// The active node set has no free slots
+		ActiveSetFull,
+		/// That `NodeType` slot is already occupied by another account
+		NodeTypeSlotTaken,
+		/// Account is not a member of the active node set
+		NotActiveNode,
+		/// Node has not registered a BLS public key
+		NoBlsKey,
+		/// Only nodes that voted to approve may contribute a partial consensus signature
+		NotApproving,
+	}
+
+	/// The pacemaker: at every block, finalize any transactions whose deadline is due.
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let due = PendingDeadlines::<T>::take(now);
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+
+			for transaction_hash in due.iter() {
+				weight = weight.saturating_add(T::DbWeight::get().reads(1));
+				if let Some(transaction) = PendingTransactions::<T>::get(transaction_hash) {
+					Self::resolve_timeout(transaction_hash, &transaction);
+					weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 4));
+				}
+			}
+
+			weight
+		}
 	}
 
 	/// Ubuntu Secure dispatchable functions
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Register a node in the Ubuntu Secure network
-		/// Each of the 5 devices must register with their node type
+		/// Add a node to the active node set (governance only)
+		/// The committee is deliberately curated: each `NodeType` slot may be held
+		/// by only one account at a time, up to `MaxActiveNodes`.
 		#[pallet::call_index(0)]
 		#[pallet::weight(T::WeightInfo::do_something())]
-		pub fn register_node(
+		pub fn add_node(
 			origin: OriginFor<T>,
+			who: T::AccountId,
 			node_type: NodeType,
+			vrf_public_key: sr25519::Public,
+			verify_key: VerifyingKey,
+			bls_public_key: bls377::Public,
 		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
+			T::MembershipOrigin::ensure_origin(origin)?;
+
+			ensure!(!RegisteredNodes::<T>::contains_key(&who), Error::<T>::AlreadyActiveNode);
+
+			let mut active = ActiveNodeSet::<T>::get();
+			ensure!(
+				!active.iter().any(|(_, existing_type)| existing_type == &node_type),
+				Error::<T>::NodeTypeSlotTaken
+			);
+			active.try_push((who.clone(), node_type.clone())).map_err(|_| Error::<T>::ActiveSetFull)?;
+			ActiveNodeSet::<T>::put(active);
 
 			// Register the node
 			RegisteredNodes::<T>::insert(&who, &node_type);
 
+			// Store the VRF public key used to verify this node's assignment proofs
+			NodeVrfKeys::<T>::insert(&who, &vrf_public_key);
+
+			// Store the key used to verify this node's signed syscalls and votes
+			NodeVerifyKeys::<T>::insert(&who, &verify_key);
+
+			// Store the key used to verify this node's partial consensus signatures
+			NodeBlsKeys::<T>::insert(&who, &bls_public_key);
+
 			// Initialize reputation score
 			NodeReputation::<T>::insert(&who, 100u32);
 
@@ -270,6 +664,69 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Remove a node from the active node set (governance only)
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::do_something())]
+		pub fn remove_node(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::MembershipOrigin::ensure_origin(origin)?;
+
+			let mut active = ActiveNodeSet::<T>::get();
+			let before = active.len();
+			active.retain(|(account, _)| account != &who);
+			ensure!(active.len() < before, Error::<T>::NotActiveNode);
+			ActiveNodeSet::<T>::put(active);
+
+			RegisteredNodes::<T>::remove(&who);
+			NodeVrfKeys::<T>::remove(&who);
+			NodeVerifyKeys::<T>::remove(&who);
+			NodeBlsKeys::<T>::remove(&who);
+			NodeReputation::<T>::remove(&who);
+
+			Self::deposit_event(Event::NodeRemoved { node_id: who });
+
+			Ok(())
+		}
+
+		/// Swap a node's slot in the active set for a different account (governance
+		/// only). Reputation resets to the default so a rotated-in node starts clean.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::do_something())]
+		pub fn swap_node(
+			origin: OriginFor<T>,
+			remove: T::AccountId,
+			add: T::AccountId,
+			vrf_public_key: sr25519::Public,
+			verify_key: VerifyingKey,
+			bls_public_key: bls377::Public,
+		) -> DispatchResult {
+			T::MembershipOrigin::ensure_origin(origin)?;
+
+			ensure!(!RegisteredNodes::<T>::contains_key(&add), Error::<T>::AlreadyActiveNode);
+
+			let mut active = ActiveNodeSet::<T>::get();
+			let idx = active.iter().position(|(account, _)| account == &remove)
+				.ok_or(Error::<T>::NotActiveNode)?;
+			let node_type = active[idx].1.clone();
+			active[idx] = (add.clone(), node_type.clone());
+			ActiveNodeSet::<T>::put(active);
+
+			RegisteredNodes::<T>::remove(&remove);
+			NodeVrfKeys::<T>::remove(&remove);
+			NodeVerifyKeys::<T>::remove(&remove);
+			NodeBlsKeys::<T>::remove(&remove);
+			NodeReputation::<T>::remove(&remove);
+
+			RegisteredNodes::<T>::insert(&add, &node_type);
+			NodeVrfKeys::<T>::insert(&add, &vrf_public_key);
+			NodeVerifyKeys::<T>::insert(&add, &verify_key);
+			NodeBlsKeys::<T>::insert(&add, &bls_public_key);
+			NodeReputation::<T>::insert(&add, 100u32); // reset reputation on rotation
+
+			Self::deposit_event(Event::NodeSwapped { removed: remove, added: add, node_type });
+
+			Ok(())
+		}
+
 		/// Submit a system call transaction for consensus
 		/// This is called when a security-critical operation needs validation
 		#[pallet::call_index(1)]
@@ -279,10 +736,28 @@ pub mod pallet {
 			syscall_type: SyscallType,
 			path: Vec<u8>,
 			flags: u32,
+			nonce: u64,
 			class: TransactionClass,
+			signature: NodeSignature,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
+			// Rotate the session seed if it has gone stale
+			Self::rotate_session_seed_if_due();
+
+			let timestamp = <frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+
+			// Verify the signature covers exactly (syscall_type, path, flags, nonce). `nonce` is
+			// chosen by the caller when they construct the call, unlike the execution-time block
+			// number, so a correctly-signed syscall can't be invalidated by queuing or a re-org
+			// landing it in a different block than the signer expected.
+			let verify_key = NodeVerifyKeys::<T>::get(&who).ok_or(Error::<T>::NodeNotRegistered)?;
+			let message = (syscall_type.clone(), path.clone(), flags, nonce).encode();
+			ensure!(
+				Self::verify_signature(&verify_key, &message, &signature),
+				Error::<T>::InvalidSignature
+			);
+
 			// Create transaction
 			let transaction = SyscallTransaction {
 				caller: who.clone(),
@@ -290,8 +765,9 @@ pub mod pallet {
 				path: path.clone(),
 				flags,
 				class,
-				timestamp: <frame_system::Pallet<T>>::block_number().saturated_into::<u64>(),
-				signature: vec![], // Simplified for Phase 1
+				nonce,
+				timestamp,
+				signature,
 			};
 
 			// Generate transaction hash
@@ -299,6 +775,10 @@ pub mod pallet {
 
 			// Store pending transaction
 			PendingTransactions::<T>::insert(&transaction_hash, &transaction);
+			TransactionSubmittedAt::<T>::insert(&transaction_hash, <frame_system::Pallet<T>>::block_number());
+
+			// Schedule the pacemaker deadline for this transaction
+			Self::schedule_deadline(&transaction_hash)?;
 
 			// Emit event
 			Self::deposit_event(Event::SyscallSubmitted {
@@ -320,9 +800,148 @@ pub mod pallet {
 			transaction_hash: T::Hash,
 			vote: Vote,
 			reason: Vec<u8>,
+			vrf_signature: VrfSignature,
+			vote_signature: NodeSignature,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::do_vote(who, transaction_hash, vote, reason, vrf_signature, vote_signature)
+		}
+
+		/// Vote on many pending transactions in a single extrinsic. Applies the same
+		/// checks as [`Self::vote_on_transaction`] to each entry, but skips and
+		/// continues past individually invalid entries (emitting a per-item result)
+		/// rather than reverting the whole batch over one stale hash.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::do_something().saturating_mul(votes.len() as u64))]
+		pub fn vote_on_transactions_batch(
+			origin: OriginFor<T>,
+			votes: BoundedVec<BatchVoteEntry<T::Hash>, T::MaxBatchSize>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			for entry in votes {
+				let transaction_hash = entry.transaction_hash;
+				let result = Self::do_vote(
+					who.clone(),
+					transaction_hash,
+					entry.vote,
+					entry.reason,
+					entry.vrf_signature,
+					entry.vote_signature,
+				);
+
+				match result {
+					Ok(()) => Self::deposit_event(Event::BatchVoteApplied {
+						voter: who.clone(),
+						transaction_hash,
+					}),
+					Err(error) => Self::deposit_event(Event::BatchVoteRejected {
+						voter: who.clone(),
+						transaction_hash,
+						error,
+					}),
+				}
+			}
+
+			Ok(())
+		}
+
+		/// Finalize consensus result and execute/deny operation
+		/// Called after votes are collected to determine final outcome
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::do_something())]
+		pub fn finalize_consensus(
+			origin: OriginFor<T>,
+			transaction_hash: T::Hash,
+		) -> DispatchResult {
+			let _who = ensure_signed(origin)?;
+
+			// Ensure transaction exists
+			ensure!(
+				PendingTransactions::<T>::contains_key(&transaction_hash),
+				Error::<T>::TransactionNotFound
+			);
+
+			// Check and finalize consensus
+			Self::check_consensus(&transaction_hash)?;
+
+			Ok(())
+		}
+
+		/// Submit a partial BLS signature over a transaction hash, contributed by an
+		/// approving node during or after voting. Once the approval threshold is met,
+		/// `check_consensus` collects every partial signature into a [`ConsensusProof`]
+		/// the enforcement layer can verify offline against each signer's registered key.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::do_something())]
+		pub fn submit_partial_signature(
+			origin: OriginFor<T>,
+			transaction_hash: T::Hash,
+			signature: bls377::Signature,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
+			ensure!(RegisteredNodes::<T>::contains_key(&who), Error::<T>::NodeNotRegistered);
+
+			let vote_record = TransactionVotes::<T>::get(&transaction_hash, &who)
+				.ok_or(Error::<T>::TransactionNotFound)?;
+			ensure!(vote_record.vote == Vote::Approve, Error::<T>::NotApproving);
+
+			let bls_public_key = NodeBlsKeys::<T>::get(&who).ok_or(Error::<T>::NoBlsKey)?;
+			ensure!(
+				sp_io::crypto::bls377_verify(&signature, transaction_hash.as_ref(), &bls_public_key),
+				Error::<T>::InvalidSignature
+			);
+
+			PartialSignatures::<T>::insert(&transaction_hash, &who, &signature);
+
+			// A proof may already be due if this was the last vote needed
+			Self::check_consensus(&transaction_hash)?;
+
+			Ok(())
+		}
+
+		/// Announce a node's VRF tranche assignment for a pending transaction, independent of
+		/// whether the node goes on to vote. A dispatchable that later errors (e.g. because the
+		/// node votes outside its open tranche) rolls back all of its storage writes, so a
+		/// certificate recorded only as a side effect of voting can never survive for a node
+		/// that never votes at all. Calling this as soon as a node learns of a transaction
+		/// leaves a durable record for [`Self::update_node_reputations`] to penalize against,
+		/// even if the node then goes silent.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::do_something())]
+		pub fn submit_assignment(
+			origin: OriginFor<T>,
+			transaction_hash: T::Hash,
+			vrf_signature: VrfSignature,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(RegisteredNodes::<T>::contains_key(&who), Error::<T>::NodeNotRegistered);
+			ensure!(
+				PendingTransactions::<T>::contains_key(&transaction_hash),
+				Error::<T>::TransactionNotFound
+			);
+
+			Self::verify_and_record_assignment(&transaction_hash, &who, &vrf_signature)?;
+
+			Ok(())
+		}
+	}
+
+	/// Helper functions for Ubuntu Secure
+	impl<T: Config> Pallet<T> {
+		/// Shared implementation behind [`Pallet::vote_on_transaction`] and
+		/// [`Pallet::vote_on_transactions_batch`].
+		fn do_vote(
+			who: T::AccountId,
+			transaction_hash: T::Hash,
+			vote: Vote,
+			reason: Vec<u8>,
+			vrf_signature: VrfSignature,
+			vote_signature: NodeSignature,
+		) -> DispatchResult {
 			// Ensure node is registered
 			let node_type = RegisteredNodes::<T>::get(&who)
 				.ok_or(Error::<T>::NodeNotRegistered)?;
@@ -345,6 +964,21 @@ pub mod pallet {
 				Error::<T>::ConsensusAlreadyReached
 			);
 
+			// Verify the vote is non-repudiably signed over (transaction_hash, vote)
+			let verify_key = NodeVerifyKeys::<T>::get(&who).ok_or(Error::<T>::NodeNotRegistered)?;
+			let message = (transaction_hash, vote.clone()).encode();
+			ensure!(
+				Self::verify_signature(&verify_key, &message, &vote_signature),
+				Error::<T>::InvalidSignature
+			);
+
+			// Verify the node's VRF assignment and record its certificate
+			let tranche = Self::verify_and_record_assignment(&transaction_hash, &who, &vrf_signature)?;
+			ensure!(
+				Self::tranche_is_open(&transaction_hash, tranche),
+				Error::<T>::NotAssigned
+			);
+
 			// Create vote record
 			let node_vote = NodeVote {
 				node_id: who.clone(),
@@ -365,75 +999,280 @@ pub mod pallet {
 				vote,
 			});
 
+			// Progress was made: extend the pacemaker deadline for this transaction
+			Self::reset_deadline(&transaction_hash)?;
+
 			// Check if consensus is reached
 			Self::check_consensus(&transaction_hash)?;
 
 			Ok(())
 		}
 
-		/// Finalize consensus result and execute/deny operation
-		/// Called after votes are collected to determine final outcome
-		#[pallet::call_index(3)]
-		#[pallet::weight(T::WeightInfo::do_something())]
-		pub fn finalize_consensus(
-			origin: OriginFor<T>,
-			transaction_hash: T::Hash,
-		) -> DispatchResult {
-			let _who = ensure_signed(origin)?;
+		/// Schedule a transaction's pacemaker deadline at `now + Timeout`.
+		fn schedule_deadline(transaction_hash: &T::Hash) -> DispatchResult {
+			let deadline = <frame_system::Pallet<T>>::block_number().saturating_add(T::Timeout::get());
+
+			PendingDeadlines::<T>::try_mutate(deadline, |due| {
+				due.try_push(*transaction_hash)
+			}).map_err(|_| Error::<T>::TooManyPendingTransactions)?;
+
+			TransactionDeadline::<T>::insert(transaction_hash, deadline);
+
+			Ok(())
+		}
+
+		/// Reset/extend a still-undecided transaction's deadline, mirroring a pacemaker
+		/// resetting its view timer whenever progress (a new vote) is made. Propagates
+		/// `schedule_deadline`'s error rather than swallowing it, since silently losing the
+		/// deadline would let the transaction hang forever instead of failing closed.
+		fn reset_deadline(transaction_hash: &T::Hash) -> DispatchResult {
+			if let Some(old_deadline) = TransactionDeadline::<T>::get(transaction_hash) {
+				PendingDeadlines::<T>::mutate(old_deadline, |due| {
+					due.retain(|hash| hash != transaction_hash);
+				});
+			}
+
+			Self::schedule_deadline(transaction_hash)
+		}
+
+		/// Finalize a transaction whose pacemaker deadline elapsed without consensus.
+		/// Class A (security-critical) operations fail closed; other classes finalize
+		/// approved only if the reputation-weighted approve tally collected so far exceeds
+		/// the weighted deny tally.
+		fn resolve_timeout(transaction_hash: &T::Hash, transaction: &SyscallTransaction<T::AccountId>) {
+			let mut votes_for = 0u32;
+			let mut votes_against = 0u32;
+			let mut total_votes = 0u32;
+			let mut weighted_for = 0u64;
+			let mut weighted_against = 0u64;
+			let mut total_weight = 0u64;
+
+			for (voter, vote_record) in TransactionVotes::<T>::iter_prefix(transaction_hash) {
+				total_votes += 1;
+				let weight = NodeReputation::<T>::get(&voter) as u64;
+				total_weight = total_weight.saturating_add(weight);
+				match vote_record.vote {
+					Vote::Approve => {
+						votes_for += 1;
+						weighted_for = weighted_for.saturating_add(weight);
+					},
+					Vote::Deny => {
+						votes_against += 1;
+						weighted_against = weighted_against.saturating_add(weight);
+					},
+					Vote::Abstain => {},
+				}
+			}
+
+			let approved = match transaction.class {
+				TransactionClass::ClassA => false, // fail-closed for security-critical ops
+				_ => weighted_for > 0 && weighted_for > weighted_against,
+			};
+
+			let consensus_result = ConsensusResult {
+				approved,
+				votes_for,
+				votes_against,
+				total_votes,
+				threshold_met: false,
+				weighted_for,
+				weighted_against,
+				total_weight,
+			};
+
+			ConsensusResults::<T>::insert(transaction_hash, &consensus_result);
+			PendingTransactions::<T>::remove(transaction_hash);
+			TransactionDeadline::<T>::remove(transaction_hash);
+
+			Self::deposit_event(Event::ConsensusTimedOut {
+				transaction_hash: *transaction_hash,
+				class: transaction.class.clone(),
+				approved,
+			});
+
+			// Assigned nodes that never voted are penalized just like a normal resolution
+			Self::update_node_reputations(transaction_hash, &consensus_result);
+		}
+
+		/// Verify a node signature against its registered key, dispatching to the
+		/// matching sr25519/ed25519 host function. Mismatched key/signature schemes
+		/// are always rejected.
+		fn verify_signature(key: &VerifyingKey, message: &[u8], signature: &NodeSignature) -> bool {
+			match (key, signature) {
+				(VerifyingKey::Sr25519(public), NodeSignature::Sr25519(sig)) => {
+					sp_io::crypto::sr25519_verify(sig, message, public)
+				},
+				(VerifyingKey::Ed25519(public), NodeSignature::Ed25519(sig)) => {
+					sp_io::crypto::ed25519_verify(sig, message, public)
+				},
+				_ => false,
+			}
+		}
+
+		/// Rotate the session seed from a recent block hash if it has gone stale.
+		fn rotate_session_seed_if_due() {
+			let now = <frame_system::Pallet<T>>::block_number();
+			let set_at = SessionSeedSetAt::<T>::get();
+			if now.saturating_sub(set_at) < T::SessionLength::get() && !SessionSeed::<T>::get().iter().all(|b| *b == 0) {
+				return;
+			}
+
+			let parent = now.saturating_sub(1u32.into());
+			let block_hash = <frame_system::Pallet<T>>::block_hash(parent);
+			let mut seed = [0u8; 32];
+			seed.copy_from_slice(block_hash.as_ref().get(0..32).unwrap_or(&[0u8; 32]));
+
+			SessionSeed::<T>::put(seed);
+			SessionSeedSetAt::<T>::put(now);
+
+			Self::deposit_event(Event::SessionSeedRotated { new_seed: seed });
+		}
+
+		/// Derive the tranche a node falls into for a transaction from its VRF output,
+		/// verify the accompanying proof, and persist the resulting certificate.
+		fn verify_and_record_assignment(
+			transaction_hash: &T::Hash,
+			who: &T::AccountId,
+			vrf_signature: &VrfSignature,
+		) -> Result<u8, DispatchError> {
+			let vrf_public_key = NodeVrfKeys::<T>::get(who).ok_or(Error::<T>::NoVrfKey)?;
+			let seed = SessionSeed::<T>::get();
+
+			let input = VrfInput::new(
+				b"ubuntu-secure-assignment",
+				[(&b"seed"[..], seed.as_ref()), (&b"tx"[..], transaction_hash.as_ref())],
+			);
 
-			// Ensure transaction exists
 			ensure!(
-				PendingTransactions::<T>::contains_key(&transaction_hash),
-				Error::<T>::TransactionNotFound
+				sp_io::crypto::sr25519_vrf_verify(&vrf_public_key, &input, vrf_signature),
+				Error::<T>::InvalidVrfProof
 			);
 
-			// Check and finalize consensus
-			Self::check_consensus(&transaction_hash)?;
+			let output_bytes = vrf_signature.output.encode();
+			let mut output = [0u8; 32];
+			output.copy_from_slice(&output_bytes[0..32.min(output_bytes.len())]);
 
-			Ok(())
+			let output_value = u32::from_be_bytes([output[0], output[1], output[2], output[3]]);
+			let tranche = if output_value < T::VrfAssignmentThreshold::get() { 0u8 } else { 1u8 };
+
+			let proof_bytes = vrf_signature.proof.encode();
+			let mut proof = [0u8; 64];
+			proof.copy_from_slice(&proof_bytes[0..64.min(proof_bytes.len())]);
+
+			let certificate = AssignmentCertificate {
+				vrf_output: output,
+				vrf_proof: proof,
+				tranche,
+			};
+			AssignmentCertificates::<T>::insert(transaction_hash, who, &certificate);
+
+			Self::deposit_event(Event::NodeAssigned {
+				transaction_hash: *transaction_hash,
+				node_id: who.clone(),
+				tranche,
+			});
+
+			Ok(tranche)
+		}
+
+		/// A node's tranche is open once it is tranche 0, or once `TrancheDelay` blocks
+		/// have elapsed since submission without enough votes, opening later tranches.
+		fn tranche_is_open(transaction_hash: &T::Hash, tranche: u8) -> bool {
+			if tranche == 0 {
+				return true;
+			}
+
+			let submitted_at = TransactionSubmittedAt::<T>::get(transaction_hash)
+				.unwrap_or_else(<frame_system::Pallet<T>>::block_number);
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			now.saturating_sub(submitted_at) >= T::TrancheDelay::get()
 		}
-	}
 
-	/// Helper functions for Ubuntu Secure
-	impl<T: Config> Pallet<T> {
 		/// Check if consensus is reached for a transaction
 		/// Ubuntu Secure requires 3/5 nodes to approve for consensus
 		fn check_consensus(transaction_hash: &T::Hash) -> DispatchResult {
+			// Consensus already resolved: a late partial signature may still complete
+			// the aggregate proof, but the tally itself must not be recomputed.
+			if ConsensusResults::<T>::contains_key(transaction_hash) {
+				Self::try_attest(transaction_hash);
+				return Ok(());
+			}
+
 			let mut votes_for = 0u32;
 			let mut votes_against = 0u32;
 			let mut total_votes = 0u32;
+			let mut weighted_for = 0u64;
+			let mut weighted_against = 0u64;
+			let mut total_weight = 0u64;
 
-			// Count all votes for this transaction
-			for (_voter, vote_record) in TransactionVotes::<T>::iter_prefix(transaction_hash) {
+			// Count all votes for this transaction, weighted by each node's reputation
+			for (voter, vote_record) in TransactionVotes::<T>::iter_prefix(transaction_hash) {
 				total_votes += 1;
+				let weight = NodeReputation::<T>::get(&voter) as u64;
+				total_weight = total_weight.saturating_add(weight);
 				match vote_record.vote {
-					Vote::Approve => votes_for += 1,
-					Vote::Deny => votes_against += 1,
-					Vote::Abstain => {}, // Abstain doesn't count toward consensus
+					Vote::Approve => {
+						votes_for += 1;
+						weighted_for = weighted_for.saturating_add(weight);
+					},
+					Vote::Deny => {
+						votes_against += 1;
+						weighted_against = weighted_against.saturating_add(weight);
+					},
+					Vote::Abstain => {}, // Abstain weight still counts toward participation
 				}
 			}
 
 			// Ubuntu Secure consensus rules:
-			// - Need at least 3 votes for consensus
-			// - Need 3/5 majority for approval (60%)
-			let threshold_met = total_votes >= 3;
-			let approved = threshold_met && votes_for >= 3;
-
-			// If we have enough votes, finalize consensus
-			if threshold_met || total_votes >= 5 {
+			// - The active set's live weight that voted (incl. abstentions) must clear
+			//   MinParticipation, so a handful of reputable nodes can't finalize alone
+			// - Of the decisive (approve + deny) weight, ApprovalFraction must approve
+			let eligible_weight: u64 = ActiveNodeSet::<T>::get()
+				.iter()
+				.map(|(account, _)| NodeReputation::<T>::get(account) as u64)
+				.sum();
+
+			let participation_met =
+				eligible_weight == 0 || Permill::from_rational(total_weight, eligible_weight) >= T::MinParticipation::get();
+
+			let decisive_weight = weighted_for.saturating_add(weighted_against);
+			let approval_met = decisive_weight > 0
+				&& Permill::from_rational(weighted_for, decisive_weight) >= T::ApprovalFraction::get();
+
+			let threshold_met = participation_met;
+			let approved = threshold_met && approval_met;
+
+			// If we have enough votes, finalize consensus. No fixed-count escape hatch here:
+			// with ActiveNodeSet sized beyond the original 5 fixed nodes, a flat vote count
+			// can clear this gate while still under the weighted MinParticipation floor, which
+			// would force-finalize (and since threshold_met would be false, force-deny) a
+			// transaction the weighted quorum rules haven't actually settled yet.
+			if threshold_met {
 				let consensus_result = ConsensusResult {
 					approved,
 					votes_for,
 					votes_against,
 					total_votes,
 					threshold_met,
+					weighted_for,
+					weighted_against,
+					total_weight,
 				};
 
 				// Store consensus result
 				ConsensusResults::<T>::insert(transaction_hash, &consensus_result);
 
-				// Remove from pending
+				// Remove from pending. Also prune the pacemaker's forward-indexed entry for
+				// this transaction (same cleanup `reset_deadline` does on every new vote), or
+				// the hash keeps occupying a slot in that future block's bounded
+				// `PendingDeadlines` vec until the block is actually reached and drained.
 				PendingTransactions::<T>::remove(transaction_hash);
+				if let Some(deadline) = TransactionDeadline::<T>::take(transaction_hash) {
+					PendingDeadlines::<T>::mutate(deadline, |due| {
+						due.retain(|hash| hash != transaction_hash);
+					});
+				}
 
 				// Emit consensus event
 				Self::deposit_event(Event::ConsensusReached {
@@ -445,11 +1284,48 @@ pub mod pallet {
 
 				// Update node reputations based on voting behavior
 				Self::update_node_reputations(transaction_hash, &consensus_result);
+
+				// Aggregate whatever partial signatures have already arrived
+				Self::try_attest(transaction_hash);
 			}
 
 			Ok(())
 		}
 
+		/// Collect every approving node's partial signature into a [`ConsensusProof`] once
+		/// consensus approved the transaction and every approving voter has contributed a
+		/// signature. A no-op if the proof already exists or consensus hasn't approved (or
+		/// resolved) yet. Unlike a single combined signature, the proof names each signer
+		/// alongside their signature: the set of approving nodes differs per transaction, so
+		/// there is no one static key an aggregate could be checked against, and an offline
+		/// verifier instead checks each entry against that node's [`NodeBlsKeys`].
+		fn try_attest(transaction_hash: &T::Hash) {
+			if ConsensusProofs::<T>::contains_key(transaction_hash) {
+				return;
+			}
+
+			let consensus_result = match ConsensusResults::<T>::get(transaction_hash) {
+				Some(result) if result.approved => result,
+				_ => return,
+			};
+
+			let signatures: Vec<(T::AccountId, bls377::Signature)> =
+				PartialSignatures::<T>::iter_prefix(transaction_hash).collect();
+
+			if (signatures.len() as u32) < consensus_result.votes_for {
+				return; // still waiting on some approving nodes to submit their partial signature
+			}
+
+			let proof = ConsensusProof { signatures };
+
+			ConsensusProofs::<T>::insert(transaction_hash, &proof);
+
+			Self::deposit_event(Event::ConsensusAttested {
+				transaction_hash: *transaction_hash,
+				proof,
+			});
+		}
+
 		/// Update node reputation scores based on voting behavior
 		/// Detect and penalize Byzantine behavior
 		fn update_node_reputations(
@@ -481,6 +1357,28 @@ pub mod pallet {
 					});
 				}
 			}
+
+			// Penalize nodes that were assigned a tranche but never voted. Tranche 0 carries
+			// the full penalty since those nodes had the whole window to vote; a node only
+			// recruited into a later tranche (because an earlier one no-showed) gets a lighter
+			// penalty, since a late no-show is a smaller failure than being absent from the
+			// start, but it still can't be free or repeatedly-recruited-and-ignored nodes would
+			// have zero incentive to ever show up.
+			for (node_id, certificate) in AssignmentCertificates::<T>::iter_prefix(transaction_hash) {
+				if TransactionVotes::<T>::contains_key(transaction_hash, &node_id) {
+					continue;
+				}
+
+				let penalty = if certificate.tranche == 0 { 5 } else { 2 };
+				let reputation = NodeReputation::<T>::get(&node_id).saturating_sub(penalty);
+				NodeReputation::<T>::insert(&node_id, reputation);
+
+				Self::deposit_event(Event::NodeNoShow {
+					transaction_hash: *transaction_hash,
+					node_id,
+					tranche: certificate.tranche,
+				});
+			}
 		}
 	}
 }
\ No newline at end of file
@@ -0,0 +1,168 @@
+//! Test runtime for the Ubuntu Secure pallet.
+
+use crate as pallet_ubuntu_secure;
+use frame_support::{parameter_types, traits::ConstU32};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Permill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		UbuntuSecure: pallet_ubuntu_secure,
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+	pub const VrfAssignmentThreshold: u32 = u32::MAX; // every node is tranche 0 unless overridden
+	pub const TrancheDelay: u64 = 5;
+	pub const SessionLength: u64 = 100;
+	pub const Timeout: u64 = 20;
+	pub const MaxTransactionsPerBlock: u32 = 16;
+	pub const MaxActiveNodes: u32 = 5;
+	pub const MaxBatchSize: u32 = 16;
+	pub ApprovalFraction: Permill = Permill::from_percent(66);
+	pub MinParticipation: Permill = Permill::from_percent(60);
+}
+
+impl pallet_ubuntu_secure::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type VrfAssignmentThreshold = VrfAssignmentThreshold;
+	type TrancheDelay = TrancheDelay;
+	type SessionLength = SessionLength;
+	type Timeout = Timeout;
+	type MaxTransactionsPerBlock = MaxTransactionsPerBlock;
+	type MembershipOrigin = EnsureRoot<u64>;
+	type MaxActiveNodes = MaxActiveNodes;
+	type ApprovalFraction = ApprovalFraction;
+	type MinParticipation = MinParticipation;
+	type MaxBatchSize = MaxBatchSize;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	storage.into()
+}
+
+/// A second mock runtime, identical to [`Test`] except `VrfAssignmentThreshold` actually
+/// splits nodes across tranche 0 and tranche 1 (instead of `u32::MAX`, which puts every node
+/// in tranche 0 and so never exercises a validator assigned to a later tranche).
+pub mod multi_tranche {
+	use super::*;
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<MultiTrancheTest>;
+	type Block = frame_system::mocking::MockBlock<MultiTrancheTest>;
+
+	frame_support::construct_runtime!(
+		pub enum MultiTrancheTest where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system,
+			UbuntuSecure: pallet_ubuntu_secure,
+		}
+	);
+
+	impl frame_system::Config for MultiTrancheTest {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type RuntimeOrigin = RuntimeOrigin;
+		type RuntimeCall = RuntimeCall;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type RuntimeEvent = RuntimeEvent;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const VrfAssignmentThreshold: u32 = u32::MAX / 2; // roughly half of outputs land in each tranche
+		pub const TrancheDelay: u64 = 5;
+		pub const SessionLength: u64 = 100;
+		pub const Timeout: u64 = 20;
+		pub const MaxTransactionsPerBlock: u32 = 16;
+		pub const MaxActiveNodes: u32 = 5;
+		pub const MaxBatchSize: u32 = 16;
+		pub ApprovalFraction: Permill = Permill::from_percent(66);
+		pub MinParticipation: Permill = Permill::from_percent(60);
+	}
+
+	impl pallet_ubuntu_secure::Config for MultiTrancheTest {
+		type RuntimeEvent = RuntimeEvent;
+		type WeightInfo = ();
+		type VrfAssignmentThreshold = VrfAssignmentThreshold;
+		type TrancheDelay = TrancheDelay;
+		type SessionLength = SessionLength;
+		type Timeout = Timeout;
+		type MaxTransactionsPerBlock = MaxTransactionsPerBlock;
+		type MembershipOrigin = EnsureRoot<u64>;
+		type MaxActiveNodes = MaxActiveNodes;
+		type ApprovalFraction = ApprovalFraction;
+		type MinParticipation = MinParticipation;
+		type MaxBatchSize = MaxBatchSize;
+	}
+
+	pub fn new_test_ext() -> sp_io::TestExternalities {
+		let storage = frame_system::GenesisConfig::default().build_storage::<MultiTrancheTest>().unwrap();
+		storage.into()
+	}
+}
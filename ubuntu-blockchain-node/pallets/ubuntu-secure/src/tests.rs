@@ -0,0 +1,528 @@
+use crate::{mock::*, pallet::*};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, traits::Get};
+use sp_core::{
+	sr25519::{self, vrf::{VrfInput, VrfSignature}, vrf::VrfSecret},
+	Pair, H256,
+};
+
+struct RegisteredNode {
+	vrf_pair: sr25519::Pair,
+	verify_pair: sr25519::Pair,
+	bls_pair: sp_core::bls377::Pair,
+}
+
+fn register_node(who: u64, node_type: NodeType) -> RegisteredNode {
+	let vrf_pair = sr25519::Pair::generate().0;
+	let verify_pair = sr25519::Pair::generate().0;
+	let bls_pair = sp_core::bls377::Pair::generate().0;
+
+	assert_ok!(UbuntuSecure::add_node(
+		RuntimeOrigin::root(),
+		who,
+		node_type,
+		vrf_pair.public(),
+		VerifyingKey::Sr25519(verify_pair.public()),
+		bls_pair.public(),
+	));
+
+	RegisteredNode { vrf_pair, verify_pair, bls_pair }
+}
+
+fn vrf_sign(pair: &sr25519::Pair, transaction_hash: H256) -> VrfSignature {
+	let seed = SessionSeed::<Test>::get();
+	let input = VrfInput::new(
+		b"ubuntu-secure-assignment",
+		[(&b"seed"[..], seed.as_ref()), (&b"tx"[..], transaction_hash.as_ref())],
+	);
+	pair.vrf_sign(&input)
+}
+
+/// Submit a `FileWrite` syscall signed by `node` and return the resulting transaction hash.
+fn submit_and_get_hash(who: u64, node: &RegisteredNode, path: &[u8], nonce: u64) -> H256 {
+	let message = (SyscallType::FileWrite, path.to_vec(), 0u32, nonce).encode();
+	let signature = NodeSignature::Sr25519(node.verify_pair.sign(&message));
+
+	assert_ok!(UbuntuSecure::submit_syscall(
+		RuntimeOrigin::signed(who),
+		SyscallType::FileWrite,
+		path.to_vec(),
+		0,
+		nonce,
+		TransactionClass::ClassB,
+		signature,
+	));
+
+	System::events()
+		.into_iter()
+		.find_map(|record| match record.event {
+			RuntimeEvent::UbuntuSecure(Event::SyscallSubmitted { transaction_hash, .. }) => {
+				Some(transaction_hash)
+			},
+			_ => None,
+		})
+		.expect("SyscallSubmitted was emitted")
+}
+
+// chunk0-2: submitted syscalls are authenticated against the submitter's registered
+// verifying key; a forged/mismatched signature over the same payload must be rejected.
+#[test]
+fn submit_syscall_rejects_invalid_signature() {
+	new_test_ext().execute_with(|| {
+		let node = register_node(1, NodeType::Laptop);
+		let other_pair = sr25519::Pair::generate().0;
+
+		let path = b"/etc/passwd".to_vec();
+		let message = (SyscallType::FileWrite, path.clone(), 0u32, 1u64).encode();
+
+		// Signed with a key that was never registered for account 1.
+		let forged = NodeSignature::Sr25519(other_pair.sign(&message));
+		assert_noop!(
+			UbuntuSecure::submit_syscall(
+				RuntimeOrigin::signed(1),
+				SyscallType::FileWrite,
+				path.clone(),
+				0,
+				1,
+				TransactionClass::ClassA,
+				forged,
+			),
+			Error::<Test>::InvalidSignature
+		);
+
+		// The genuinely registered key for the same payload is accepted.
+		let genuine = NodeSignature::Sr25519(node.verify_pair.sign(&message));
+		assert_ok!(UbuntuSecure::submit_syscall(
+			RuntimeOrigin::signed(1),
+			SyscallType::FileWrite,
+			path,
+			0,
+			1,
+			TransactionClass::ClassA,
+			genuine,
+		));
+	});
+}
+
+// chunk0-5: once enough approving nodes have each submitted a valid partial BLS
+// signature, check_consensus must fold them into a single ConsensusProof.
+#[test]
+fn approving_votes_aggregate_into_consensus_proof() {
+	new_test_ext().execute_with(|| {
+		let node_a = register_node(1, NodeType::Laptop);
+		let node_b = register_node(2, NodeType::Phone);
+		let node_c = register_node(3, NodeType::Pi);
+		// Two more nodes that never vote, so the third approval (not the second) is the one
+		// that lifts participation to the 60% MinParticipation floor and finalizes.
+		let _node_d = register_node(4, NodeType::Cloud);
+		let _node_e = register_node(5, NodeType::Friend);
+
+		let path = b"/tmp/out".to_vec();
+		let message = (SyscallType::FileWrite, path.clone(), 0u32, 7u64).encode();
+		let signature = NodeSignature::Sr25519(node_a.verify_pair.sign(&message));
+
+		assert_ok!(UbuntuSecure::submit_syscall(
+			RuntimeOrigin::signed(1),
+			SyscallType::FileWrite,
+			path,
+			0,
+			7,
+			TransactionClass::ClassA,
+			signature,
+		));
+
+		let transaction_hash = System::events()
+			.into_iter()
+			.find_map(|record| match record.event {
+				RuntimeEvent::UbuntuSecure(Event::SyscallSubmitted { transaction_hash, .. }) => {
+					Some(transaction_hash)
+				},
+				_ => None,
+			})
+			.expect("SyscallSubmitted was emitted");
+
+		for (who, node) in [(1u64, &node_a), (2u64, &node_b), (3u64, &node_c)] {
+			let vrf_signature = vrf_sign(&node.vrf_pair, transaction_hash);
+			let vote_message = (transaction_hash, Vote::Approve).encode();
+			let vote_signature = NodeSignature::Sr25519(node.verify_pair.sign(&vote_message));
+
+			assert_ok!(UbuntuSecure::vote_on_transaction(
+				RuntimeOrigin::signed(who),
+				transaction_hash,
+				Vote::Approve,
+				Vec::new(),
+				vrf_signature,
+				vote_signature,
+			));
+		}
+
+		assert!(ConsensusResults::<Test>::get(transaction_hash).unwrap().approved);
+
+		for (who, node) in [(1u64, &node_a), (2u64, &node_b), (3u64, &node_c)] {
+			assert_ok!(UbuntuSecure::submit_partial_signature(
+				RuntimeOrigin::signed(who),
+				transaction_hash,
+				node.bls_pair.sign(transaction_hash.as_ref()),
+			));
+		}
+
+		let proof = ConsensusProofs::<Test>::get(transaction_hash).expect("proof aggregated");
+		assert_eq!(proof.signatures.len(), 3);
+		for (signer, signature) in &proof.signatures {
+			let bls_public_key = NodeBlsKeys::<Test>::get(signer).expect("signer is registered");
+			assert!(sp_io::crypto::bls377_verify(signature, transaction_hash.as_ref(), &bls_public_key));
+		}
+	});
+}
+
+// chunk0-1: a node's VRF assignment must actually verify on-chain. A proof produced with a
+// keypair other than the one registered for that node has to be rejected, and a genuine one
+// is recorded as an `AssignmentCertificate` the pallet can later use to penalize no-shows.
+#[test]
+fn vote_rejects_forged_vrf_proof_and_records_assignment_certificate() {
+	new_test_ext().execute_with(|| {
+		let node = register_node(1, NodeType::Laptop);
+		let unregistered_vrf_pair = sr25519::Pair::generate().0;
+
+		let transaction_hash = submit_and_get_hash(1, &node, b"/tmp/vrf", 1);
+
+		let vote_message = (transaction_hash, Vote::Approve).encode();
+		let vote_signature = NodeSignature::Sr25519(node.verify_pair.sign(&vote_message));
+
+		// Proof generated from a keypair never registered as this node's VRF key.
+		let forged_vrf_signature = vrf_sign(&unregistered_vrf_pair, transaction_hash);
+		assert_noop!(
+			UbuntuSecure::vote_on_transaction(
+				RuntimeOrigin::signed(1),
+				transaction_hash,
+				Vote::Approve,
+				Vec::new(),
+				forged_vrf_signature,
+				vote_signature.clone(),
+			),
+			Error::<Test>::InvalidVrfProof
+		);
+		assert!(AssignmentCertificates::<Test>::get(transaction_hash, 1).is_none());
+
+		// The node's genuine VRF keypair verifies and is recorded as an assignment certificate.
+		let genuine_vrf_signature = vrf_sign(&node.vrf_pair, transaction_hash);
+		assert_ok!(UbuntuSecure::vote_on_transaction(
+			RuntimeOrigin::signed(1),
+			transaction_hash,
+			Vote::Approve,
+			Vec::new(),
+			genuine_vrf_signature,
+			vote_signature,
+		));
+		let certificate = AssignmentCertificates::<Test>::get(transaction_hash, 1)
+			.expect("genuine VRF proof recorded an assignment certificate");
+		// VrfAssignmentThreshold is u32::MAX in the mock, so every node lands in tranche 0.
+		assert_eq!(certificate.tranche, 0);
+	});
+}
+
+// chunk0-3: a transaction that never collects enough votes must not block its syscall
+// forever. Once the pacemaker deadline elapses, the pallet finalizes it unilaterally,
+// fail-closed for Class A operations.
+#[test]
+fn pacemaker_times_out_class_a_transaction_fail_closed() {
+	new_test_ext().execute_with(|| {
+		let node = register_node(1, NodeType::Laptop);
+
+		let message = (SyscallType::FileWrite, b"/etc/shadow".to_vec(), 0u32, 1u64).encode();
+		let signature = NodeSignature::Sr25519(node.verify_pair.sign(&message));
+		assert_ok!(UbuntuSecure::submit_syscall(
+			RuntimeOrigin::signed(1),
+			SyscallType::FileWrite,
+			b"/etc/shadow".to_vec(),
+			0,
+			1,
+			TransactionClass::ClassA,
+			signature,
+		));
+
+		let transaction_hash = System::events()
+			.into_iter()
+			.find_map(|record| match record.event {
+				RuntimeEvent::UbuntuSecure(Event::SyscallSubmitted { transaction_hash, .. }) => {
+					Some(transaction_hash)
+				},
+				_ => None,
+			})
+			.expect("SyscallSubmitted was emitted");
+
+		// No one ever votes. Advance past the pacemaker deadline and run on_initialize for
+		// that block, as the executive would.
+		let deadline = System::block_number() + Timeout::get();
+		System::set_block_number(deadline);
+		<UbuntuSecure as frame_support::traits::Hooks<u64>>::on_initialize(deadline);
+
+		let result = ConsensusResults::<Test>::get(transaction_hash)
+			.expect("pacemaker finalized the transaction unilaterally");
+		assert!(!result.approved, "ClassA operations fail closed on timeout");
+		assert!(PendingTransactions::<Test>::get(transaction_hash).is_none());
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::UbuntuSecure(Event::ConsensusTimedOut { transaction_hash: h, approved: false, .. })
+				if h == transaction_hash
+		)));
+	});
+}
+
+// chunk0-4: membership of the active node set is governed, not open. Only MembershipOrigin
+// may add a node, each NodeType slot holds a single account, and removing a node frees its
+// slot for someone else.
+#[test]
+fn membership_is_governed_and_slots_are_exclusive() {
+	new_test_ext().execute_with(|| {
+		let vrf_pair = sr25519::Pair::generate().0;
+		let verify_pair = sr25519::Pair::generate().0;
+		let bls_pair = sp_core::bls377::Pair::generate().0;
+
+		// An ordinary signed account can't add itself to the committee.
+		assert_noop!(
+			UbuntuSecure::add_node(
+				RuntimeOrigin::signed(1),
+				1,
+				NodeType::Laptop,
+				vrf_pair.public(),
+				VerifyingKey::Sr25519(verify_pair.public()),
+				bls_pair.public(),
+			),
+			sp_runtime::traits::BadOrigin,
+		);
+
+		let _node = register_node(1, NodeType::Laptop);
+
+		// A second account can't take the Laptop slot while it's occupied.
+		let other_vrf_pair = sr25519::Pair::generate().0;
+		let other_verify_pair = sr25519::Pair::generate().0;
+		let other_bls_pair = sp_core::bls377::Pair::generate().0;
+		assert_noop!(
+			UbuntuSecure::add_node(
+				RuntimeOrigin::root(),
+				2,
+				NodeType::Laptop,
+				other_vrf_pair.public(),
+				VerifyingKey::Sr25519(other_verify_pair.public()),
+				other_bls_pair.public(),
+			),
+			Error::<Test>::NodeTypeSlotTaken
+		);
+
+		// Removing the incumbent frees the slot for the other account.
+		assert_ok!(UbuntuSecure::remove_node(RuntimeOrigin::root(), 1));
+		assert!(!RegisteredNodes::<Test>::contains_key(1));
+		assert_ok!(UbuntuSecure::add_node(
+			RuntimeOrigin::root(),
+			2,
+			NodeType::Laptop,
+			other_vrf_pair.public(),
+			VerifyingKey::Sr25519(other_verify_pair.public()),
+			other_bls_pair.public(),
+		));
+		assert_eq!(NodeReputation::<Test>::get(2), 100);
+	});
+}
+
+// chunk0-6: a node clearing a backlog submits many votes in one extrinsic. An individually
+// invalid entry (voting on a transaction that doesn't exist) is skipped and reported, but
+// doesn't revert the node's other, valid votes in the same batch.
+#[test]
+fn batch_vote_skips_invalid_entries_without_reverting_the_rest() {
+	new_test_ext().execute_with(|| {
+		let node_a = register_node(1, NodeType::Laptop);
+		let node_b = register_node(2, NodeType::Phone);
+
+		let transaction_hash = submit_and_get_hash(1, &node_a, b"/tmp/batch", 1);
+		let bogus_hash = H256::repeat_byte(0xAB);
+
+		let vrf_signature = vrf_sign(&node_b.vrf_pair, transaction_hash);
+		let vote_message = (transaction_hash, Vote::Approve).encode();
+		let vote_signature = NodeSignature::Sr25519(node_b.verify_pair.sign(&vote_message));
+
+		let bogus_vrf_signature = vrf_sign(&node_b.vrf_pair, bogus_hash);
+		let bogus_vote_message = (bogus_hash, Vote::Approve).encode();
+		let bogus_vote_signature = NodeSignature::Sr25519(node_b.verify_pair.sign(&bogus_vote_message));
+
+		let votes = frame_support::BoundedVec::try_from(vec![
+			BatchVoteEntry {
+				transaction_hash,
+				vote: Vote::Approve,
+				reason: Vec::new(),
+				vrf_signature,
+				vote_signature,
+			},
+			BatchVoteEntry {
+				transaction_hash: bogus_hash,
+				vote: Vote::Approve,
+				reason: Vec::new(),
+				vrf_signature: bogus_vrf_signature,
+				vote_signature: bogus_vote_signature,
+			},
+		])
+		.unwrap();
+
+		assert_ok!(UbuntuSecure::vote_on_transactions_batch(RuntimeOrigin::signed(2), votes));
+
+		assert!(TransactionVotes::<Test>::get(transaction_hash, 2).is_some());
+		assert!(System::events().iter().any(|record| matches!(
+			&record.event,
+			RuntimeEvent::UbuntuSecure(Event::BatchVoteApplied { voter: 2, transaction_hash: h }) if *h == transaction_hash
+		)));
+		assert!(System::events().iter().any(|record| matches!(
+			&record.event,
+			RuntimeEvent::UbuntuSecure(Event::BatchVoteRejected { voter: 2, transaction_hash: h, .. }) if *h == bogus_hash
+		)));
+	});
+}
+
+// chunk0-7: finalization must require the active set's weighted participation floor, not
+// just a flat vote count, so two of five equally-weighted nodes (well under MinParticipation)
+// can't force a decision however one-sided their votes are.
+#[test]
+fn weighted_quorum_requires_participation_floor_by_weight() {
+	new_test_ext().execute_with(|| {
+		let nodes = [
+			register_node(1, NodeType::Laptop),
+			register_node(2, NodeType::Phone),
+			register_node(3, NodeType::Pi),
+			register_node(4, NodeType::Cloud),
+			register_node(5, NodeType::Friend),
+		];
+
+		let transaction_hash = submit_and_get_hash(1, &nodes[0], b"/tmp/quorum", 1);
+
+		let vote_as = |who: u64, node: &RegisteredNode| {
+			let vrf_signature = vrf_sign(&node.vrf_pair, transaction_hash);
+			let vote_message = (transaction_hash, Vote::Approve).encode();
+			let vote_signature = NodeSignature::Sr25519(node.verify_pair.sign(&vote_message));
+			UbuntuSecure::vote_on_transaction(
+				RuntimeOrigin::signed(who),
+				transaction_hash,
+				Vote::Approve,
+				Vec::new(),
+				vrf_signature,
+				vote_signature,
+			)
+		};
+
+		// Two of five equally-weighted nodes approving is only 40% of eligible weight,
+		// below the 60% MinParticipation floor in the mock, regardless of unanimity so far.
+		assert_ok!(vote_as(1, &nodes[0]));
+		assert_ok!(vote_as(2, &nodes[1]));
+		assert!(ConsensusResults::<Test>::get(transaction_hash).is_none());
+
+		// A third approval brings weighted turnout to 60%, clearing participation; with all
+		// decisive weight in favor, the approval fraction gate passes too.
+		assert_ok!(vote_as(3, &nodes[2]));
+		let result = ConsensusResults::<Test>::get(transaction_hash).expect("finalized");
+		assert!(result.threshold_met);
+		assert!(result.approved);
+		assert_eq!(result.weighted_for, 300);
+		assert_eq!(result.total_weight, 300);
+	});
+}
+
+// chunk0-1: the default mock's VrfAssignmentThreshold (u32::MAX) puts every node in tranche
+// 0, so no test ever exercised a node actually assigned to a later tranche. This uses a
+// second mock runtime whose threshold genuinely splits tranche assignment, to cover a
+// tranche-1 node being rejected before TrancheDelay elapses and accepted after.
+#[test]
+fn tranche_one_node_must_wait_out_tranche_delay_before_voting() {
+	use crate::mock::multi_tranche::{
+		new_test_ext as multi_tranche_test_ext, MultiTrancheTest, RuntimeOrigin as MtOrigin,
+		System as MtSystem, UbuntuSecure as MtUbuntuSecure,
+	};
+
+	multi_tranche_test_ext().execute_with(|| {
+		let submitter_vrf_pair = sr25519::Pair::generate().0;
+		let submitter_verify_pair = sr25519::Pair::generate().0;
+		let submitter_bls_pair = sp_core::bls377::Pair::generate().0;
+		assert_ok!(MtUbuntuSecure::add_node(
+			MtOrigin::root(),
+			1,
+			NodeType::Laptop,
+			submitter_vrf_pair.public(),
+			VerifyingKey::Sr25519(submitter_verify_pair.public()),
+			submitter_bls_pair.public(),
+		));
+
+		let message = (SyscallType::FileWrite, b"/tmp/tranche".to_vec(), 0u32, 1u64).encode();
+		let signature = NodeSignature::Sr25519(submitter_verify_pair.sign(&message));
+		assert_ok!(MtUbuntuSecure::submit_syscall(
+			MtOrigin::signed(1),
+			SyscallType::FileWrite,
+			b"/tmp/tranche".to_vec(),
+			0,
+			1,
+			TransactionClass::ClassB,
+			signature,
+		));
+		let transaction_hash = MtSystem::events()
+			.into_iter()
+			.find_map(|record| match record.event {
+				crate::mock::multi_tranche::RuntimeEvent::UbuntuSecure(
+					Event::SyscallSubmitted { transaction_hash, .. },
+				) => Some(transaction_hash),
+				_ => None,
+			})
+			.expect("SyscallSubmitted was emitted");
+
+		// Search for a VRF keypair whose output for this transaction lands in tranche 1
+		// under this runtime's threshold; the VRF output is a deterministic function of the
+		// keypair and transaction, so this terminates quickly in practice.
+		let (tranche1_vrf_pair, tranche1_vrf_signature) = loop {
+			let pair = sr25519::Pair::generate().0;
+			let vrf_signature = vrf_sign(&pair, transaction_hash);
+			let output_bytes = vrf_signature.output.encode();
+			let mut output = [0u8; 32];
+			output.copy_from_slice(&output_bytes[0..32.min(output_bytes.len())]);
+			let output_value = u32::from_be_bytes([output[0], output[1], output[2], output[3]]);
+			if output_value >= crate::mock::multi_tranche::VrfAssignmentThreshold::get() {
+				break (pair, vrf_signature);
+			}
+		};
+
+		let verify_pair = sr25519::Pair::generate().0;
+		let bls_pair = sp_core::bls377::Pair::generate().0;
+		assert_ok!(MtUbuntuSecure::add_node(
+			MtOrigin::root(),
+			2,
+			NodeType::Phone,
+			tranche1_vrf_pair.public(),
+			VerifyingKey::Sr25519(verify_pair.public()),
+			bls_pair.public(),
+		));
+
+		let vote_message = (transaction_hash, Vote::Approve).encode();
+		let vote_signature = NodeSignature::Sr25519(verify_pair.sign(&vote_message));
+
+		// Before TrancheDelay elapses, the tranche-1 node's vote is rejected.
+		assert_noop!(
+			MtUbuntuSecure::vote_on_transaction(
+				MtOrigin::signed(2),
+				transaction_hash,
+				Vote::Approve,
+				Vec::new(),
+				tranche1_vrf_signature.clone(),
+				vote_signature.clone(),
+			),
+			Error::<MultiTrancheTest>::NotAssigned
+		);
+
+		// Once TrancheDelay blocks have passed, the same node's vote is accepted.
+		MtSystem::set_block_number(MtSystem::block_number() + crate::mock::multi_tranche::TrancheDelay::get());
+		assert_ok!(MtUbuntuSecure::vote_on_transaction(
+			MtOrigin::signed(2),
+			transaction_hash,
+			Vote::Approve,
+			Vec::new(),
+			tranche1_vrf_signature,
+			vote_signature,
+		));
+		let certificate = AssignmentCertificates::<MultiTrancheTest>::get(transaction_hash, 2)
+			.expect("tranche-1 node's vote recorded an assignment certificate");
+		assert_eq!(certificate.tranche, 1);
+	});
+}